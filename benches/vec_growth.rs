@@ -0,0 +1,59 @@
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+use dust::lists::{GrowthStrategy, Vec};
+
+const STRATEGIES: [(&str, GrowthStrategy); 4] = [
+    ("doubling", GrowthStrategy::Doubling),
+    ("one_and_half", GrowthStrategy::OneAndHalf),
+    ("chunk_64", GrowthStrategy::Chunk(64)),
+    ("chunk_1024", GrowthStrategy::Chunk(1024)),
+];
+
+const PUSHES: [usize; 3] = [100, 10_000, 100_000];
+
+fn push_bytes(c: &mut Criterion) {
+    let mut group = c.benchmark_group("push_u8");
+    for pushes in PUSHES {
+        for (name, strategy) in STRATEGIES {
+            group.bench_with_input(BenchmarkId::new(name, pushes), &pushes, |b, &n| {
+                b.iter(|| {
+                    let mut list = Vec::with_growth_strategy(strategy);
+                    for i in 0..n {
+                        list.push(i as u8);
+                    }
+                    black_box(list)
+                })
+            });
+        }
+        group.bench_with_input(BenchmarkId::new("std", pushes), &pushes, |b, &n| {
+            b.iter(|| {
+                let mut list = std::vec::Vec::new();
+                for i in 0..n {
+                    list.push(i as u8);
+                }
+                black_box(list)
+            })
+        });
+    }
+    group.finish();
+}
+
+fn push_strings(c: &mut Criterion) {
+    let mut group = c.benchmark_group("push_string");
+    for pushes in PUSHES {
+        for (name, strategy) in STRATEGIES {
+            group.bench_with_input(BenchmarkId::new(name, pushes), &pushes, |b, &n| {
+                b.iter(|| {
+                    let mut list = Vec::with_growth_strategy(strategy);
+                    for _ in 0..n {
+                        list.push(String::new());
+                    }
+                    black_box(list)
+                })
+            });
+        }
+    }
+    group.finish();
+}
+
+criterion_group!(benches, push_bytes, push_strings);
+criterion_main!(benches);
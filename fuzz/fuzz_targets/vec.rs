@@ -0,0 +1,12 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+
+// Shared with the differential tests in the main crate.
+#[allow(dead_code)]
+#[path = "../../tests/model/mod.rs"]
+mod model;
+
+fuzz_target!(|ops: Vec<model::VecOp>| {
+    model::run_vec(&ops);
+});
@@ -0,0 +1,433 @@
+use crate::caches::CacheStats;
+use crate::lists::node_list::{Linked, Links, NodeList};
+use crate::maps::key_ref::{KeyRef, KeyWrapper};
+use crate::maps::ChainedHashMap;
+use std::borrow::Borrow;
+use std::collections::hash_map::RandomState;
+use std::hash::{BuildHasher, Hash};
+use std::ptr::{self, NonNull};
+
+struct Node<K, V> {
+    key: K,
+    value: V,
+    freq: usize,
+    links: Links<Node<K, V>>,
+}
+
+impl<K, V> Linked for Node<K, V> {
+    unsafe fn links(node: *mut Self) -> *mut Links<Self> {
+        ptr::addr_of_mut!((*node).links)
+    }
+}
+
+pub struct LfuCache<K, V, S = RandomState> {
+    index: ChainedHashMap<KeyRef<K>, NonNull<Node<K, V>>, S>,
+    // Entries grouped by use count, oldest at the head of each list so ties
+    // evict the least recently used.
+    freqs: ChainedHashMap<usize, NodeList<Node<K, V>>>,
+    min_freq: usize,
+    capacity: usize,
+    on_evict: Option<Box<dyn FnMut(K, V)>>,
+    stats: CacheStats,
+}
+
+impl<K, V> LfuCache<K, V, RandomState> {
+    pub fn new(capacity: usize) -> Self {
+        Self::with_hasher(capacity, RandomState::new())
+    }
+}
+
+impl<K, V, S> LfuCache<K, V, S> {
+    pub fn with_hasher(capacity: usize, hash_builder: S) -> Self {
+        assert!(capacity > 0, "Capacity must be greater than zero");
+
+        LfuCache {
+            index: ChainedHashMap::with_hasher(hash_builder),
+            freqs: ChainedHashMap::new(),
+            min_freq: 0,
+            capacity,
+            on_evict: None,
+            stats: CacheStats::default(),
+        }
+    }
+
+    /// Registers a callback that receives every entry evicted to make room.
+    /// Entries taken out with `remove`, `pop_lfu` or `clear` are not reported.
+    pub fn set_eviction_callback<F>(&mut self, callback: F)
+    where
+        F: FnMut(K, V) + 'static,
+    {
+        self.on_evict = Some(Box::new(callback));
+    }
+
+    pub fn len(&self) -> usize {
+        self.index.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.index.is_empty()
+    }
+
+    pub fn is_full(&self) -> bool {
+        self.index.len() == self.capacity
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    pub fn stats(&self) -> CacheStats {
+        self.stats
+    }
+
+    pub fn reset_stats(&mut self) {
+        self.stats = CacheStats::default();
+    }
+
+    pub fn peek_lfu(&self) -> Option<(&K, &V)> {
+        let list = self.freqs.get(&self.min_freq)?;
+        list.head().map(|node_ptr| unsafe {
+            let node = &*node_ptr.as_ptr();
+            (&node.key, &node.value)
+        })
+    }
+
+    // Takes the node out of its frequency list, dropping the list if that
+    // empties it. The caller owns the node afterwards.
+    fn unlink(&mut self, node_ptr: NonNull<Node<K, V>>) {
+        let freq = unsafe { (*node_ptr.as_ptr()).freq };
+        let list = self
+            .freqs
+            .get_mut(&freq)
+            .expect("Node is linked into its frequency list");
+
+        unsafe { list.unlink(node_ptr) };
+        if list.is_empty() {
+            self.freqs.remove(&freq);
+        }
+    }
+
+    fn link_back(&mut self, node_ptr: NonNull<Node<K, V>>) {
+        let freq = unsafe { (*node_ptr.as_ptr()).freq };
+        if !self.freqs.contains_key(&freq) {
+            self.freqs.insert(freq, NodeList::new());
+        }
+
+        let list = self.freqs.get_mut(&freq).expect("list was just inserted");
+        unsafe { list.link_back(node_ptr) };
+    }
+
+    fn touch(&mut self, node_ptr: NonNull<Node<K, V>>) {
+        let freq = unsafe { (*node_ptr.as_ptr()).freq };
+        self.unlink(node_ptr);
+        if self.min_freq == freq && !self.freqs.contains_key(&freq) {
+            self.min_freq = freq + 1;
+        }
+
+        unsafe { (*node_ptr.as_ptr()).freq = freq + 1 };
+        self.link_back(node_ptr);
+    }
+
+    // Unlinks and frees a node that is leaving the cache. Only emptying the
+    // lowest frequency list moves the minimum, and only then does it have to
+    // be searched for among the lists that are left.
+    fn remove_node(&mut self, node_ptr: NonNull<Node<K, V>>) -> (K, V) {
+        let node = self.take_node(node_ptr);
+        if node.freq == self.min_freq && !self.freqs.contains_key(&node.freq) {
+            self.min_freq = self.freqs.keys().copied().min().unwrap_or(0);
+        }
+        (node.key, node.value)
+    }
+
+    // Unlinks and frees a node without updating `min_freq`.
+    fn take_node(&mut self, node_ptr: NonNull<Node<K, V>>) -> Box<Node<K, V>> {
+        self.unlink(node_ptr);
+        unsafe { Box::from_raw(node_ptr.as_ptr()) }
+    }
+}
+
+impl<K, V, S> LfuCache<K, V, S>
+where
+    K: Hash + Eq,
+    S: BuildHasher,
+{
+    /// Inserts or updates an entry. Updating counts as a use of the entry.
+    /// Returns the previous value for the key, if any.
+    pub fn put(&mut self, key: K, value: V) -> Option<V> {
+        if let Some(&node_ptr) = self.index.get(KeyWrapper::from_ref(&key)) {
+            self.touch(node_ptr);
+            let old = unsafe { std::mem::replace(&mut (*node_ptr.as_ptr()).value, value) };
+            return Some(old);
+        }
+
+        if self.index.len() == self.capacity {
+            // The new entry brings `min_freq` back to 1, so there is no need
+            // to find the next lowest frequency after evicting.
+            if let Some(node_ptr) = self.lfu_node() {
+                let node = self.take_node(node_ptr);
+                self.evicted(node.key, node.value);
+            }
+        }
+
+        let node = Box::new(Node {
+            key,
+            value,
+            freq: 1,
+            links: Links::new(),
+        });
+        let node_ptr = unsafe { NonNull::new_unchecked(Box::into_raw(node)) };
+        let key_ref = KeyRef {
+            key: unsafe { &(*node_ptr.as_ptr()).key },
+        };
+
+        self.index.insert(key_ref, node_ptr);
+        self.link_back(node_ptr);
+        self.min_freq = 1;
+        None
+    }
+
+    pub fn get<Q>(&mut self, key: &Q) -> Option<&V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.get_mut(key).map(|value| &*value)
+    }
+
+    pub fn get_mut<Q>(&mut self, key: &Q) -> Option<&mut V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        let node_ptr = self.index.get(KeyWrapper::from_ref(key)).copied();
+        self.stats.record(node_ptr.is_some());
+
+        let node_ptr = node_ptr?;
+        self.touch(node_ptr);
+        Some(unsafe { &mut (*node_ptr.as_ptr()).value })
+    }
+
+    /// Looks up `key` without updating its use count or the hit/miss counters.
+    pub fn peek<Q>(&self, key: &Q) -> Option<&V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        let node_ptr = *self.index.get(KeyWrapper::from_ref(key))?;
+        Some(unsafe { &(*node_ptr.as_ptr()).value })
+    }
+
+    pub fn frequency<Q>(&self, key: &Q) -> Option<usize>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        let node_ptr = *self.index.get(KeyWrapper::from_ref(key))?;
+        Some(unsafe { (*node_ptr.as_ptr()).freq })
+    }
+
+    pub fn contains<Q>(&self, key: &Q) -> bool
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.index.contains_key(KeyWrapper::from_ref(key))
+    }
+
+    pub fn remove<Q>(&mut self, key: &Q) -> Option<V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        let node_ptr = self.index.remove(KeyWrapper::from_ref(key))?;
+        let (_, value) = self.remove_node(node_ptr);
+        Some(value)
+    }
+
+    pub fn pop_lfu(&mut self) -> Option<(K, V)> {
+        let node_ptr = self.lfu_node()?;
+        Some(self.remove_node(node_ptr))
+    }
+
+    // Takes the least frequently used node out of the index.
+    fn lfu_node(&mut self) -> Option<NonNull<Node<K, V>>> {
+        let node_ptr = self.freqs.get(&self.min_freq)?.head()?;
+        unsafe {
+            self.index
+                .remove(KeyWrapper::from_ref(&(*node_ptr.as_ptr()).key));
+        }
+        Some(node_ptr)
+    }
+
+    /// Changes the capacity, evicting least frequently used entries if it shrinks.
+    pub fn resize(&mut self, capacity: usize) {
+        assert!(capacity > 0, "Capacity must be greater than zero");
+
+        self.capacity = capacity;
+        self.evict_to(capacity);
+    }
+
+    pub fn clear(&mut self) {
+        self.index.clear();
+        self.freqs.clear();
+        self.min_freq = 0;
+    }
+
+    fn evict_to(&mut self, len: usize) {
+        while self.index.len() > len {
+            if let Some((key, value)) = self.pop_lfu() {
+                self.evicted(key, value);
+            }
+        }
+    }
+
+    fn evicted(&mut self, key: K, value: V) {
+        if let Some(on_evict) = self.on_evict.as_mut() {
+            on_evict(key, value);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::LfuCache;
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    #[test]
+    fn put_evicts_least_frequently_used() {
+        let mut cache = LfuCache::new(2);
+        cache.put("a", 1);
+        cache.put("b", 2);
+        cache.get("a");
+        cache.get("a");
+        cache.get("b");
+        cache.put("c", 3);
+
+        assert!(cache.contains("a"));
+        assert!(!cache.contains("b"));
+        assert!(cache.contains("c"));
+        assert_eq!(cache.frequency("a"), Some(3));
+        assert_eq!(cache.frequency("c"), Some(1));
+    }
+
+    #[test]
+    fn ties_evict_least_recently_used() {
+        let mut cache = LfuCache::new(3);
+        cache.put(1, "one");
+        cache.put(2, "two");
+        cache.put(3, "three");
+        cache.get(&2);
+        cache.get(&1);
+        cache.get(&3);
+
+        assert_eq!(cache.peek_lfu(), Some((&2, &"two")));
+        cache.put(4, "four");
+        assert!(!cache.contains(&2));
+        assert_eq!(cache.peek_lfu(), Some((&4, &"four")));
+    }
+
+    #[test]
+    fn put_replaces_existing_and_counts_as_use() {
+        let mut cache = LfuCache::new(2);
+        cache.put("a", 1);
+
+        assert_eq!(cache.put("a", 10), Some(1));
+        assert_eq!(cache.frequency("a"), Some(2));
+        assert_eq!(cache.len(), 1);
+    }
+
+    #[test]
+    fn peek_does_not_touch_frequency() {
+        let mut cache = LfuCache::new(2);
+        cache.put("a", 1);
+
+        assert_eq!(cache.peek("a"), Some(&1));
+        assert_eq!(cache.frequency("a"), Some(1));
+        assert_eq!(cache.stats().lookups(), 0);
+    }
+
+    #[test]
+    fn remove_works() {
+        let mut cache = LfuCache::new(3);
+        cache.put("a", 1);
+        cache.put("b", 2);
+        cache.get("b");
+
+        assert_eq!(cache.remove("a"), Some(1));
+        assert_eq!(cache.remove("a"), None);
+        assert_eq!(cache.peek_lfu(), Some((&"b", &2)));
+        assert_eq!(cache.pop_lfu(), Some(("b", 2)));
+        assert!(cache.is_empty());
+    }
+
+    #[test]
+    fn lowest_frequency_follows_removals() {
+        let mut cache = LfuCache::new(4);
+        cache.put("a", 1);
+        cache.put("b", 2);
+        cache.put("c", 3);
+        cache.get("b");
+        cache.get("c");
+        cache.get("c");
+
+        assert_eq!(cache.remove("b"), Some(2));
+        assert_eq!(cache.peek_lfu(), Some((&"a", &1)));
+        assert_eq!(cache.remove("a"), Some(1));
+        assert_eq!(cache.peek_lfu(), Some((&"c", &3)));
+
+        cache.clear();
+        assert!(cache.is_empty());
+        assert_eq!(cache.peek_lfu(), None);
+        cache.put("d", 4);
+        assert_eq!(cache.peek_lfu(), Some((&"d", &4)));
+    }
+
+    #[test]
+    fn eviction_callback_works() {
+        let evicted = Rc::new(RefCell::new(Vec::new()));
+        let sink = Rc::clone(&evicted);
+
+        let mut cache = LfuCache::new(2);
+        cache.set_eviction_callback(move |key, value| sink.borrow_mut().push((key, value)));
+        cache.put(String::from("a"), 1);
+        cache.get("a");
+        cache.put(String::from("b"), 2);
+        cache.put(String::from("c"), 3);
+        cache.resize(1);
+
+        assert_eq!(
+            *evicted.borrow(),
+            vec![(String::from("b"), 2), (String::from("c"), 3)]
+        );
+        assert_eq!(cache.peek("a"), Some(&1));
+    }
+
+    #[test]
+    fn stats_work() {
+        let mut cache = LfuCache::new(2);
+        cache.put("a", 1);
+
+        assert_eq!(cache.get("a"), Some(&1));
+        assert_eq!(cache.get("b"), None);
+
+        let stats = cache.stats();
+        assert_eq!(stats.hits, 1);
+        assert_eq!(stats.misses, 1);
+    }
+
+    #[test]
+    fn many_entries_work() {
+        let mut cache = LfuCache::new(100);
+        for i in 0..1000 {
+            cache.put(i, i);
+            if i % 2 == 0 {
+                cache.get(&i);
+            }
+        }
+
+        assert_eq!(cache.len(), 100);
+        assert!(cache.contains(&998));
+        assert!(!cache.contains(&997));
+    }
+}
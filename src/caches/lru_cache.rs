@@ -0,0 +1,254 @@
+use crate::caches::CacheStats;
+use crate::maps::{linked_hash_map, LinkedHashMap, Order};
+use std::borrow::Borrow;
+use std::collections::hash_map::RandomState;
+use std::fmt::{self, Display};
+use std::hash::{BuildHasher, Hash};
+
+pub struct LruCache<K, V, S = RandomState> {
+    map: LinkedHashMap<K, V, S>,
+    capacity: usize,
+    on_evict: Option<Box<dyn FnMut(K, V)>>,
+    stats: CacheStats,
+}
+
+impl<K, V> LruCache<K, V, RandomState> {
+    pub fn new(capacity: usize) -> Self {
+        Self::with_hasher(capacity, RandomState::new())
+    }
+}
+
+impl<K, V, S> LruCache<K, V, S> {
+    pub fn with_hasher(capacity: usize, hash_builder: S) -> Self {
+        assert!(capacity > 0, "Capacity must be greater than zero");
+
+        LruCache {
+            map: LinkedHashMap::with_order_and_hasher(Order::Access, hash_builder),
+            capacity,
+            on_evict: None,
+            stats: CacheStats::default(),
+        }
+    }
+
+    /// Registers a callback that receives every entry evicted to make room.
+    /// Entries taken out with `remove`, `pop_lru` or `clear` are not reported.
+    pub fn set_eviction_callback<F>(&mut self, callback: F)
+    where
+        F: FnMut(K, V) + 'static,
+    {
+        self.on_evict = Some(Box::new(callback));
+    }
+
+    pub fn len(&self) -> usize {
+        self.map.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.map.is_empty()
+    }
+
+    pub fn is_full(&self) -> bool {
+        self.map.len() == self.capacity
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    pub fn stats(&self) -> CacheStats {
+        self.stats
+    }
+
+    pub fn reset_stats(&mut self) {
+        self.stats = CacheStats::default();
+    }
+
+    /// Iterates from the least to the most recently used entry.
+    pub fn iter(&self) -> linked_hash_map::Iter<'_, K, V> {
+        self.map.iter()
+    }
+}
+
+impl<K, V, S> LruCache<K, V, S>
+where
+    K: Hash + Eq,
+    S: BuildHasher,
+{
+    /// Inserts or updates an entry, marking it most recently used. Returns the
+    /// previous value for the key, if any.
+    pub fn put(&mut self, key: K, value: V) -> Option<V> {
+        if !self.map.contains_key(&key) {
+            self.evict_to(self.capacity - 1);
+        }
+
+        self.map.insert(key, value)
+    }
+
+    pub fn get<Q>(&mut self, key: &Q) -> Option<&V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        let value = self.map.get(key);
+        self.stats.record(value.is_some());
+        value
+    }
+
+    pub fn get_mut<Q>(&mut self, key: &Q) -> Option<&mut V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        let value = self.map.get_mut(key);
+        self.stats.record(value.is_some());
+        value
+    }
+
+    /// Looks up `key` without updating recency or the hit/miss counters.
+    pub fn peek<Q>(&self, key: &Q) -> Option<&V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.map.peek(key)
+    }
+
+    pub fn peek_lru(&self) -> Option<(&K, &V)> {
+        self.map.front()
+    }
+
+    pub fn contains<Q>(&self, key: &Q) -> bool
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.map.contains_key(key)
+    }
+
+    pub fn remove<Q>(&mut self, key: &Q) -> Option<V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.map.remove(key)
+    }
+
+    pub fn pop_lru(&mut self) -> Option<(K, V)> {
+        self.map.pop_front()
+    }
+
+    /// Changes the capacity, evicting least recently used entries if it shrinks.
+    pub fn resize(&mut self, capacity: usize) {
+        assert!(capacity > 0, "Capacity must be greater than zero");
+
+        self.capacity = capacity;
+        self.evict_to(capacity);
+    }
+
+    pub fn clear(&mut self) {
+        self.map.clear();
+    }
+
+    fn evict_to(&mut self, len: usize) {
+        while self.map.len() > len {
+            if let Some((key, value)) = self.map.pop_front() {
+                if let Some(on_evict) = self.on_evict.as_mut() {
+                    on_evict(key, value);
+                }
+            }
+        }
+    }
+}
+
+impl<K: Display, V: Display, S> Display for LruCache<K, V, S> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.map)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::LruCache;
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    #[test]
+    fn put_evicts_least_recently_used() {
+        let mut cache = LruCache::new(2);
+        cache.put("a", 1);
+        cache.put("b", 2);
+        cache.get("a");
+        cache.put("c", 3);
+        println!("cache: {}", cache);
+
+        assert!(cache.contains("a"));
+        assert!(!cache.contains("b"));
+        assert!(cache.contains("c"));
+        assert_eq!(cache.len(), 2);
+    }
+
+    #[test]
+    fn put_replaces_existing_without_eviction() {
+        let mut cache = LruCache::new(2);
+        cache.put("a", 1);
+        cache.put("b", 2);
+
+        assert_eq!(cache.put("a", 10), Some(1));
+        assert_eq!(cache.len(), 2);
+        assert_eq!(cache.peek_lru(), Some((&"b", &2)));
+    }
+
+    #[test]
+    fn peek_does_not_touch_recency() {
+        let mut cache = LruCache::new(2);
+        cache.put("a", 1);
+        cache.put("b", 2);
+
+        assert_eq!(cache.peek("a"), Some(&1));
+        cache.put("c", 3);
+
+        assert!(!cache.contains("a"));
+        assert_eq!(cache.stats().lookups(), 0);
+    }
+
+    #[test]
+    fn eviction_callback_works() {
+        let evicted = Rc::new(RefCell::new(Vec::new()));
+        let sink = Rc::clone(&evicted);
+
+        let mut cache = LruCache::new(3);
+        cache.set_eviction_callback(move |key, value| sink.borrow_mut().push((key, value)));
+        for i in 0..5 {
+            cache.put(i, i * 10);
+        }
+        cache.remove(&4);
+        cache.resize(1);
+
+        assert_eq!(*evicted.borrow(), vec![(0, 0), (1, 10), (2, 20)]);
+        assert_eq!(cache.iter().collect::<Vec<_>>(), vec![(&3, &30)]);
+    }
+
+    #[test]
+    fn stats_work() {
+        let mut cache = LruCache::new(2);
+        cache.put("a", 1);
+
+        assert_eq!(cache.get("a"), Some(&1));
+        assert_eq!(cache.get("b"), None);
+        *cache.get_mut("a").unwrap() += 1;
+
+        let stats = cache.stats();
+        assert_eq!(stats.hits, 2);
+        assert_eq!(stats.misses, 1);
+        assert!((stats.hit_ratio() - 2.0 / 3.0).abs() < f64::EPSILON);
+
+        cache.reset_stats();
+        assert_eq!(cache.stats().lookups(), 0);
+    }
+
+    #[test]
+    #[should_panic]
+    fn new_panics_for_zero_capacity() {
+        let _cache: LruCache<i32, i32> = LruCache::new(0);
+    }
+}
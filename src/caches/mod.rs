@@ -0,0 +1,7 @@
+pub mod cache_stats;
+pub mod lfu_cache;
+pub mod lru_cache;
+
+pub use self::cache_stats::CacheStats;
+pub use self::lfu_cache::LfuCache;
+pub use self::lru_cache::LruCache;
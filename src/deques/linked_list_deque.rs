@@ -1,6 +1,8 @@
 use std::collections::LinkedList;
-use std::fmt::{self, Display};
+use std::fmt::{self, Debug, Display};
+use std::ops::{Index, IndexMut};
 
+#[derive(Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
 pub struct LinkedListDeque<T: Copy + PartialEq> {
     buffer: LinkedList<T>,
 }
@@ -37,10 +39,52 @@ impl<T: Copy + PartialEq> LinkedListDeque<T> {
     }
 
     pub fn collect(&self) -> Vec<T> {
+        self.buffer.iter().copied().collect::<Vec<T>>()
+    }
+}
+
+impl<T: Copy + PartialEq> Default for LinkedListDeque<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Copy + PartialEq> FromIterator<T> for LinkedListDeque<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut deque = Self::new();
+        deque.extend(iter);
+        deque
+    }
+}
+
+impl<T: Copy + PartialEq> Extend<T> for LinkedListDeque<T> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for elt in iter {
+            self.push_back(elt);
+        }
+    }
+}
+
+impl<T: Copy + PartialEq> Index<usize> for LinkedListDeque<T> {
+    type Output = T;
+
+    fn index(&self, index: usize) -> &T {
+        self.buffer.iter().nth(index).expect("Index out of bounds")
+    }
+}
+
+impl<T: Copy + PartialEq> IndexMut<usize> for LinkedListDeque<T> {
+    fn index_mut(&mut self, index: usize) -> &mut T {
         self.buffer
-            .iter()
-            .map(|elm| elm.clone())
-            .collect::<Vec<T>>()
+            .iter_mut()
+            .nth(index)
+            .expect("Index out of bounds")
+    }
+}
+
+impl<T: Copy + PartialEq + Debug> Debug for LinkedListDeque<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.buffer.iter()).finish()
     }
 }
 
@@ -64,6 +108,7 @@ impl<T: Copy + PartialEq + Display> Display for LinkedListDeque<T> {
 #[cfg(test)]
 mod tests {
     use super::LinkedListDeque;
+    use crate::deques::VecDeque;
 
     #[test]
     fn push_front_works() {
@@ -155,4 +200,23 @@ mod tests {
 
         assert_eq!(list.len(), 0);
     }
+
+    #[test]
+    fn agrees_with_vec_deque() {
+        let mut deque: LinkedListDeque<i32> = (1..=3).collect();
+        let mut vec_deque: VecDeque<i32> = (1..=3).collect();
+        deque.push_front(0);
+        vec_deque.push_front(0);
+        deque[2] = 7;
+        vec_deque[2] = 7;
+
+        assert_eq!(deque[0], vec_deque[0]);
+        assert_eq!(deque[3], vec_deque[3]);
+        assert_eq!(deque.collect(), vec_deque.collect());
+        assert_eq!(format!("{:?}", deque), format!("{:?}", vec_deque));
+
+        let unchanged: LinkedListDeque<i32> = (0..=3).collect();
+        assert!(unchanged < deque);
+        assert_eq!(deque.clone(), deque);
+    }
 }
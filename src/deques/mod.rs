@@ -1,5 +1,5 @@
-mod linked_list_deque;
-mod vec_deque;
+pub mod linked_list_deque;
+pub mod vec_deque;
 
 pub use self::linked_list_deque::LinkedListDeque;
 pub use self::vec_deque::VecDeque;
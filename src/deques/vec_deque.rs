@@ -1,66 +1,471 @@
-use std::fmt::{self, Display};
-
-pub struct VecDeque<T: Copy + PartialEq> {
-    buffer: Vec<T>,
+use crate::allocator::Global;
+use crate::lists::raw_vec::{handle_reserve, RawVec};
+use std::cmp::Ordering;
+use std::fmt::{self, Debug, Display};
+use std::hash::{Hash, Hasher};
+use std::mem::{self, MaybeUninit};
+use std::ops::{Index, IndexMut};
+use std::ptr;
+use std::slice;
+
+/// A double-ended queue over a growable ring buffer.
+///
+/// The elements occupy `len` slots starting at `head` and wrap around the end
+/// of the buffer, so pushing and popping at either end is amortized O(1).
+pub struct VecDeque<T> {
+    buffer: RawVec<T>,
+    head: usize,
+    len: usize,
 }
 
-impl<T: Copy + PartialEq> VecDeque<T> {
+impl<T> VecDeque<T> {
     pub fn new() -> Self {
-        VecDeque { buffer: Vec::new() }
+        VecDeque {
+            buffer: RawVec::new_in(Global),
+            head: 0,
+            len: 0,
+        }
+    }
+
+    pub fn with_capacity(capacity: usize) -> Self {
+        VecDeque {
+            buffer: RawVec::with_capacity_in(capacity, Global),
+            head: 0,
+            len: 0,
+        }
+    }
+
+    fn ptr(&self) -> *mut T {
+        self.buffer.ptr.as_ptr()
+    }
+
+    fn cap(&self) -> usize {
+        self.buffer.cap
+    }
+
+    // Maps a logical index onto its slot in the buffer. Written to avoid
+    // overflow, since zero-sized types have a capacity of usize::MAX.
+    fn to_physical(&self, index: usize) -> usize {
+        let to_end = self.cap() - self.head;
+        if index >= to_end {
+            index - to_end
+        } else {
+            self.head + index
+        }
+    }
+
+    // The slot before `slot`, wrapping to the end of the buffer.
+    fn prev_slot(&self, slot: usize) -> usize {
+        if slot == 0 {
+            self.cap() - 1
+        } else {
+            slot - 1
+        }
+    }
+
+    // Moves the element in slot `src` to slot `dst`, leaving `src` logically
+    // uninitialized.
+    unsafe fn move_slot(&mut self, src: usize, dst: usize) {
+        ptr::copy(self.ptr().add(src), self.ptr().add(dst), 1);
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.cap()
+    }
+
+    pub fn reserve(&mut self, additional: usize) {
+        let old_cap = self.cap();
+        handle_reserve(self.buffer.grow_amortized(self.len, additional));
+        unsafe { self.handle_capacity_increase(old_cap) };
+    }
+
+    fn grow(&mut self) {
+        let old_cap = self.cap();
+        self.buffer.grow();
+        unsafe { self.handle_capacity_increase(old_cap) };
+    }
+
+    // After the buffer grows, a wrapped run of elements is no longer
+    // contiguous with the new space. Move whichever half is shorter so the
+    // ring is valid again under the new capacity.
+    unsafe fn handle_capacity_increase(&mut self, old_cap: usize) {
+        let new_cap = self.cap();
+        if self.head <= old_cap - self.len {
+            return;
+        }
+
+        let head_len = old_cap - self.head;
+        let tail_len = self.len - head_len;
+        if tail_len < head_len && new_cap - old_cap >= tail_len {
+            // [o o H h h] -> [. . H h h o o . .]
+            ptr::copy_nonoverlapping(self.ptr(), self.ptr().add(old_cap), tail_len);
+        } else {
+            // [o o H h h] -> [o o . . . . . H h h]
+            let new_head = new_cap - head_len;
+            ptr::copy(
+                self.ptr().add(self.head),
+                self.ptr().add(new_head),
+                head_len,
+            );
+            self.head = new_head;
+        }
     }
 
     pub fn push_front(&mut self, element: T) {
-        let mut new_buffer = vec![element];
-        new_buffer.append(self.buffer.as_mut());
-        self.buffer = new_buffer;
+        if self.len == self.cap() {
+            self.grow();
+        }
+
+        self.head = self.prev_slot(self.head);
+        unsafe { ptr::write(self.ptr().add(self.head), element) };
+        self.len += 1;
     }
 
     pub fn push_back(&mut self, element: T) {
-        self.buffer.push(element)
+        if self.len == self.cap() {
+            self.grow();
+        }
+
+        unsafe { ptr::write(self.ptr().add(self.to_physical(self.len)), element) };
+        self.len += 1;
     }
 
+    /// Shifts whichever side of `index` is shorter to make room.
     pub fn insert(&mut self, index: usize, element: T) {
-        self.buffer.insert(index, element)
+        assert!(index <= self.len, "index out of bounds");
+        if self.len == self.cap() {
+            self.grow();
+        }
+
+        unsafe {
+            if index < self.len - index {
+                // Move the front run one slot towards the front.
+                for i in 0..index {
+                    let src = self.to_physical(i);
+                    self.move_slot(src, self.prev_slot(src));
+                }
+                self.head = self.prev_slot(self.head);
+            } else {
+                // Move the back run one slot towards the back.
+                for i in (index..self.len).rev() {
+                    self.move_slot(self.to_physical(i), self.to_physical(i + 1));
+                }
+            }
+            ptr::write(self.ptr().add(self.to_physical(index)), element);
+        }
+        self.len += 1;
     }
 
     pub fn pop_front(&mut self) -> Option<T> {
-        if self.buffer.len() == 0 {
+        if self.len == 0 {
             return None;
         }
 
-        let result = self.buffer[0];
-        self.buffer = self.buffer[1..].to_vec();
-        Some(result)
+        let element = unsafe { ptr::read(self.ptr().add(self.head)) };
+        self.head = self.to_physical(1);
+        self.len -= 1;
+        Some(element)
     }
 
     pub fn pop_back(&mut self) -> Option<T> {
-        self.buffer.pop()
+        if self.len == 0 {
+            return None;
+        }
+
+        self.len -= 1;
+        unsafe { Some(ptr::read(self.ptr().add(self.to_physical(self.len)))) }
     }
 
+    /// Shifts whichever side of `index` is shorter to close the gap.
     pub fn remove(&mut self, index: usize) -> T {
-        self.buffer.remove(index)
+        assert!(index < self.len, "index out of bounds");
+
+        unsafe {
+            let element = ptr::read(self.ptr().add(self.to_physical(index)));
+            if index < self.len - index - 1 {
+                // Move the front run one slot towards the back.
+                for i in (0..index).rev() {
+                    self.move_slot(self.to_physical(i), self.to_physical(i + 1));
+                }
+                self.head = self.to_physical(1);
+            } else {
+                // Move the back run one slot towards the front.
+                for i in index + 1..self.len {
+                    self.move_slot(self.to_physical(i), self.to_physical(i - 1));
+                }
+            }
+            self.len -= 1;
+            element
+        }
+    }
+
+    pub fn truncate(&mut self, len: usize) {
+        while self.len > len {
+            self.pop_back();
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.truncate(0)
     }
 
     pub fn len(&self) -> usize {
-        self.buffer.len()
+        self.len
     }
 
     pub fn is_empty(&self) -> bool {
-        self.buffer.is_empty()
+        self.len == 0
+    }
+
+    pub fn get(&self, index: usize) -> Option<&T> {
+        if index >= self.len {
+            return None;
+        }
+        unsafe { Some(&*self.ptr().add(self.to_physical(index))) }
     }
 
+    pub fn get_mut(&mut self, index: usize) -> Option<&mut T> {
+        if index >= self.len {
+            return None;
+        }
+        unsafe { Some(&mut *self.ptr().add(self.to_physical(index))) }
+    }
+
+    pub fn front(&self) -> Option<&T> {
+        self.get(0)
+    }
+
+    pub fn back(&self) -> Option<&T> {
+        self.len.checked_sub(1).and_then(|index| self.get(index))
+    }
+
+    // The ranges of slots holding the front and back runs of elements.
+    fn slice_ranges(&self) -> ((usize, usize), (usize, usize)) {
+        if self.len == 0 {
+            return ((0, 0), (0, 0));
+        }
+
+        let to_end = self.cap() - self.head;
+        if self.len <= to_end {
+            ((self.head, self.len), (0, 0))
+        } else {
+            ((self.head, to_end), (0, self.len - to_end))
+        }
+    }
+
+    /// Returns the elements as two slices, front run first. The second slice
+    /// is empty unless the elements wrap around the end of the buffer.
+    pub fn as_slices(&self) -> (&[T], &[T]) {
+        let ((front, front_len), (back, back_len)) = self.slice_ranges();
+        unsafe {
+            (
+                slice::from_raw_parts(self.ptr().add(front), front_len),
+                slice::from_raw_parts(self.ptr().add(back), back_len),
+            )
+        }
+    }
+
+    pub fn as_mut_slices(&mut self) -> (&mut [T], &mut [T]) {
+        let ((front, front_len), (back, back_len)) = self.slice_ranges();
+        unsafe {
+            (
+                slice::from_raw_parts_mut(self.ptr().add(front), front_len),
+                slice::from_raw_parts_mut(self.ptr().add(back), back_len),
+            )
+        }
+    }
+
+    /// Rearranges the buffer so the elements sit in a single contiguous slice,
+    /// and returns it. O(capacity) when the elements wrap; otherwise they are
+    /// left where they are and this is O(1).
+    pub fn make_contiguous(&mut self) -> &mut [T] {
+        if mem::size_of::<T>() == 0 {
+            self.head = 0;
+        } else if self.head > self.cap() - self.len {
+            // Rotating uninitialized slots along with the elements is fine as
+            // long as nothing reads them as `T`.
+            let slots =
+                unsafe { slice::from_raw_parts_mut(self.ptr() as *mut MaybeUninit<T>, self.cap()) };
+            slots.rotate_left(self.head);
+            self.head = 0;
+        }
+        self.as_mut_slices().0
+    }
+
+    /// Rotates the first `n` elements to the back.
+    pub fn rotate_left(&mut self, n: usize) {
+        assert!(n <= self.len, "rotation out of bounds");
+        if n <= self.len - n {
+            self.rotate_front_to_back(n);
+        } else {
+            self.rotate_back_to_front(self.len - n);
+        }
+    }
+
+    /// Rotates the last `n` elements to the front.
+    pub fn rotate_right(&mut self, n: usize) {
+        assert!(n <= self.len, "rotation out of bounds");
+        if n <= self.len - n {
+            self.rotate_back_to_front(n);
+        } else {
+            self.rotate_front_to_back(self.len - n);
+        }
+    }
+
+    // When the buffer is full these are a pure change of `head`; otherwise
+    // each step moves one element across the gap.
+    fn rotate_front_to_back(&mut self, n: usize) {
+        if self.len == self.cap() {
+            self.head = self.to_physical(n);
+            return;
+        }
+        for _ in 0..n {
+            unsafe {
+                let dst = self.to_physical(self.len);
+                self.move_slot(self.head, dst);
+            }
+            self.head = self.to_physical(1);
+        }
+    }
+
+    fn rotate_back_to_front(&mut self, n: usize) {
+        if self.len == self.cap() {
+            self.head = self.to_physical(self.len - n);
+            return;
+        }
+        for _ in 0..n {
+            let src = self.to_physical(self.len - 1);
+            self.head = self.prev_slot(self.head);
+            unsafe { self.move_slot(src, self.head) };
+        }
+    }
+
+    pub fn iter(&self) -> Iter<'_, T> {
+        let (front, back) = self.as_slices();
+        Iter {
+            front: front.iter(),
+            back: back.iter(),
+        }
+    }
+
+    pub fn iter_mut(&mut self) -> IterMut<'_, T> {
+        let (front, back) = self.as_mut_slices();
+        IterMut {
+            front: front.iter_mut(),
+            back: back.iter_mut(),
+        }
+    }
+}
+
+impl<T: Clone> VecDeque<T> {
     pub fn collect(&self) -> Vec<T> {
-        self.buffer
-            .iter()
-            .map(|elm| elm.clone())
-            .collect::<Vec<T>>()
+        self.iter().cloned().collect()
+    }
+}
+
+impl<T> Default for VecDeque<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> Drop for VecDeque<T> {
+    fn drop(&mut self) {
+        // Drops the back run even if an element in the front run panics.
+        struct Dropper<'a, T>(&'a mut [T]);
+
+        impl<T> Drop for Dropper<'_, T> {
+            fn drop(&mut self) {
+                unsafe { ptr::drop_in_place(self.0) }
+            }
+        }
+
+        let (front, back) = self.as_mut_slices();
+        let _back = Dropper(back);
+        unsafe { ptr::drop_in_place(front) };
+        // deallocation is handled by RawVec
+    }
+}
+
+impl<T: Clone> Clone for VecDeque<T> {
+    fn clone(&self) -> Self {
+        let mut deque = VecDeque::with_capacity(self.len);
+        deque.extend(self.iter().cloned());
+        deque
+    }
+}
+
+impl<T> FromIterator<T> for VecDeque<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut deque = Self::new();
+        deque.extend(iter);
+        deque
+    }
+}
+
+impl<T> Extend<T> for VecDeque<T> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        let iter = iter.into_iter();
+        self.reserve(iter.size_hint().0);
+        for elt in iter {
+            self.push_back(elt);
+        }
+    }
+}
+
+impl<T> Index<usize> for VecDeque<T> {
+    type Output = T;
+
+    fn index(&self, index: usize) -> &T {
+        self.get(index).expect("Index out of bounds")
+    }
+}
+
+impl<T> IndexMut<usize> for VecDeque<T> {
+    fn index_mut(&mut self, index: usize) -> &mut T {
+        self.get_mut(index).expect("Index out of bounds")
     }
 }
 
-impl<T: Copy + PartialEq + Display> Display for VecDeque<T> {
+impl<T: Debug> Debug for VecDeque<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+impl<T: PartialEq> PartialEq for VecDeque<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.len == other.len && self.iter().eq(other.iter())
+    }
+}
+
+impl<T: Eq> Eq for VecDeque<T> {}
+
+impl<T: Hash> Hash for VecDeque<T> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.len.hash(state);
+        for elt in self.iter() {
+            elt.hash(state);
+        }
+    }
+}
+
+impl<T: PartialOrd> PartialOrd for VecDeque<T> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        self.iter().partial_cmp(other.iter())
+    }
+}
+
+impl<T: Ord> Ord for VecDeque<T> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.iter().cmp(other.iter())
+    }
+}
+
+impl<T: Display> Display for VecDeque<T> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(f, "[")?;
-        let mut iter = self.buffer.iter();
+        let mut iter = self.iter();
 
         if let Some(value) = iter.next() {
             write!(f, "{}", value)?;
@@ -74,9 +479,138 @@ impl<T: Copy + PartialEq + Display> Display for VecDeque<T> {
     }
 }
 
+pub struct Iter<'a, T> {
+    front: slice::Iter<'a, T>,
+    back: slice::Iter<'a, T>,
+}
+
+impl<'a, T> Iterator for Iter<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<&'a T> {
+        self.front.next().or_else(|| {
+            // Swap so the exhausted run is never polled again.
+            mem::swap(&mut self.front, &mut self.back);
+            self.front.next()
+        })
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.front.len() + self.back.len();
+        (len, Some(len))
+    }
+}
+
+impl<'a, T> DoubleEndedIterator for Iter<'a, T> {
+    fn next_back(&mut self) -> Option<&'a T> {
+        self.back.next_back().or_else(|| {
+            mem::swap(&mut self.front, &mut self.back);
+            self.back.next_back()
+        })
+    }
+}
+
+impl<T> ExactSizeIterator for Iter<'_, T> {}
+
+pub struct IterMut<'a, T> {
+    front: slice::IterMut<'a, T>,
+    back: slice::IterMut<'a, T>,
+}
+
+impl<'a, T> Iterator for IterMut<'a, T> {
+    type Item = &'a mut T;
+
+    fn next(&mut self) -> Option<&'a mut T> {
+        self.front.next().or_else(|| {
+            mem::swap(&mut self.front, &mut self.back);
+            self.front.next()
+        })
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.front.len() + self.back.len();
+        (len, Some(len))
+    }
+}
+
+impl<'a, T> DoubleEndedIterator for IterMut<'a, T> {
+    fn next_back(&mut self) -> Option<&'a mut T> {
+        self.back.next_back().or_else(|| {
+            mem::swap(&mut self.front, &mut self.back);
+            self.back.next_back()
+        })
+    }
+}
+
+impl<T> ExactSizeIterator for IterMut<'_, T> {}
+
+pub struct IntoIter<T> {
+    deque: VecDeque<T>,
+}
+
+impl<T> Iterator for IntoIter<T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        self.deque.pop_front()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.deque.len, Some(self.deque.len))
+    }
+}
+
+impl<T> DoubleEndedIterator for IntoIter<T> {
+    fn next_back(&mut self) -> Option<T> {
+        self.deque.pop_back()
+    }
+}
+
+impl<T> ExactSizeIterator for IntoIter<T> {}
+
+impl<T> IntoIterator for VecDeque<T> {
+    type Item = T;
+    type IntoIter = IntoIter<T>;
+
+    fn into_iter(self) -> IntoIter<T> {
+        IntoIter { deque: self }
+    }
+}
+
+impl<'a, T> IntoIterator for &'a VecDeque<T> {
+    type Item = &'a T;
+    type IntoIter = Iter<'a, T>;
+
+    fn into_iter(self) -> Iter<'a, T> {
+        self.iter()
+    }
+}
+
+impl<'a, T> IntoIterator for &'a mut VecDeque<T> {
+    type Item = &'a mut T;
+    type IntoIter = IterMut<'a, T>;
+
+    fn into_iter(self) -> IterMut<'a, T> {
+        self.iter_mut()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::VecDeque;
+    use crate::test_util::{hash_of, DropCounter};
+    use std::cell::Cell;
+
+    // A full deque of capacity 8 whose head sits at `offset`, holding 0..len.
+    fn wrapped(offset: usize, len: usize) -> VecDeque<i32> {
+        let mut deque = VecDeque::with_capacity(8);
+        for _ in 0..offset {
+            deque.push_back(-1);
+            deque.pop_front();
+        }
+        deque.extend(0..len as i32);
+        deque
+    }
 
     #[test]
     fn push_front_works() {
@@ -230,4 +764,165 @@ mod tests {
         assert_eq!(vec, vec![2, 4]);
         assert_eq!(list.len(), 2);
     }
+
+    #[test]
+    fn comparisons_ignore_where_the_buffer_wraps() {
+        let mut deque = wrapped(6, 5);
+        let flat: VecDeque<i32> = (0..5).collect();
+        assert_eq!(flat.as_slices().1, &[]);
+        assert_eq!(deque, flat);
+        assert_eq!(hash_of(&deque), hash_of(&flat));
+        assert_eq!(format!("{:?}", deque), "[0, 1, 2, 3, 4]");
+        assert_eq!(deque.clone(), flat);
+
+        // Index 3 sits in the wrapped-around part of the buffer.
+        deque[3] = 9;
+        assert!(flat < deque);
+    }
+
+    #[test]
+    fn wraps_around_the_buffer() {
+        let mut deque = wrapped(6, 5);
+        assert_eq!(deque.capacity(), 8);
+        assert_eq!(deque.as_slices(), (&[0, 1][..], &[2, 3, 4][..]));
+        assert_eq!(deque.front(), Some(&0));
+        assert_eq!(deque.back(), Some(&4));
+        assert_eq!(deque.get(3), Some(&3));
+        assert_eq!(deque.get(5), None);
+
+        deque.push_front(-1);
+        assert_eq!(deque.collect(), vec![-1, 0, 1, 2, 3, 4]);
+    }
+
+    #[test]
+    fn grow_keeps_wrapped_elements_in_order() {
+        // The short back run gets moved past the old end.
+        let mut deque = wrapped(2, 8);
+        deque.push_back(8);
+        assert!(deque.iter().copied().eq(0..9));
+
+        // The short front run gets moved to the new end.
+        let mut deque = wrapped(6, 8);
+        deque.push_back(8);
+        assert!(deque.iter().copied().eq(0..9));
+
+        let mut deque = wrapped(7, 8);
+        deque.reserve(20);
+        assert!(deque.capacity() >= 28);
+        assert!(deque.iter().copied().eq(0..8));
+    }
+
+    #[test]
+    fn insert_and_remove_match_std_at_every_offset() {
+        for offset in 0..8 {
+            for len in 0..8 {
+                for index in 0..=len {
+                    let mut deque = wrapped(offset, len);
+                    let mut model: std::collections::VecDeque<i32> = (0..len as i32).collect();
+                    deque.insert(index, 99);
+                    model.insert(index, 99);
+                    assert!(
+                        deque.iter().eq(model.iter()),
+                        "insert {offset} {len} {index}"
+                    );
+
+                    let mut deque = wrapped(offset, len);
+                    let mut model: std::collections::VecDeque<i32> = (0..len as i32).collect();
+                    if index < len {
+                        assert_eq!(deque.remove(index), model.remove(index).unwrap());
+                        assert!(
+                            deque.iter().eq(model.iter()),
+                            "remove {offset} {len} {index}"
+                        );
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn make_contiguous_works() {
+        let mut deque = wrapped(5, 6);
+        assert!(!deque.as_slices().1.is_empty());
+        assert_eq!(deque.make_contiguous(), &[0, 1, 2, 3, 4, 5]);
+        assert_eq!(deque.as_slices(), (&[0, 1, 2, 3, 4, 5][..], &[][..]));
+
+        deque.push_front(-1);
+        deque.make_contiguous().sort_by(|a, b| b.cmp(a));
+        assert_eq!(deque.collect(), vec![5, 4, 3, 2, 1, 0, -1]);
+    }
+
+    #[test]
+    fn rotate_works() {
+        for (offset, len) in [(0, 5), (6, 5), (3, 8)] {
+            let mut deque = wrapped(offset, len);
+            let mut model: std::collections::VecDeque<i32> = (0..len as i32).collect();
+            for n in 0..=len {
+                deque.rotate_left(n);
+                model.rotate_left(n);
+                assert!(deque.iter().eq(model.iter()));
+
+                deque.rotate_right(n / 2);
+                model.rotate_right(n / 2);
+                assert!(deque.iter().eq(model.iter()));
+            }
+        }
+    }
+
+    #[test]
+    fn iterators_work() {
+        let mut deque = wrapped(6, 5);
+        for value in deque.iter_mut() {
+            *value *= 10;
+        }
+
+        let mut iter = deque.iter();
+        assert_eq!(iter.len(), 5);
+        assert_eq!(iter.next(), Some(&0));
+        assert_eq!(iter.next_back(), Some(&40));
+        assert_eq!(iter.next_back(), Some(&30));
+        assert_eq!(iter.next(), Some(&10));
+        assert_eq!(iter.next(), Some(&20));
+        assert_eq!(iter.next(), None);
+        assert_eq!(iter.next_back(), None);
+
+        assert!(deque.into_iter().rev().eq([40, 30, 20, 10, 0]));
+    }
+
+    #[test]
+    fn drops_each_element_once() {
+        let drops = Cell::new(0);
+        let mut deque = VecDeque::with_capacity(4);
+        for _ in 0..3 {
+            deque.push_back(DropCounter::new(&drops));
+            drop(deque.pop_front());
+        }
+        assert_eq!(drops.get(), 3);
+
+        // Leaves the elements wrapped around the end of the buffer.
+        for _ in 0..6 {
+            deque.push_back(DropCounter::new(&drops));
+        }
+        deque.truncate(4);
+        assert_eq!(drops.get(), 5);
+
+        let mut iter = deque.into_iter();
+        drop(iter.next());
+        assert_eq!(drops.get(), 6);
+        drop(iter);
+        assert_eq!(drops.get(), 9);
+    }
+
+    #[test]
+    fn zero_sized_types_work() {
+        let mut deque = VecDeque::new();
+        for _ in 0..10 {
+            deque.push_front(());
+            deque.push_back(());
+        }
+        deque.rotate_left(3);
+        assert_eq!(deque.make_contiguous().len(), 20);
+        assert_eq!(deque.remove(7), ());
+        assert_eq!(deque.iter().count(), 19);
+    }
 }
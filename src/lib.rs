@@ -1,5 +1,11 @@
+pub mod allocator;
+pub mod caches;
 pub mod deques;
 pub mod lists;
+pub mod maps;
 pub mod queues;
+pub mod sets;
 pub mod stacks;
+#[cfg(test)]
+pub(crate) mod test_util;
 pub mod trees;
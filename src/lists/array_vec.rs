@@ -0,0 +1,394 @@
+use std::error::Error;
+use std::fmt::{self, Debug, Display};
+use std::mem::{self, MaybeUninit};
+use std::ops::{Deref, DerefMut};
+use std::ptr;
+
+/// Returned when an `ArrayVec` is full, handing back the element that did not
+/// fit.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub struct CapacityError<T> {
+    element: T,
+}
+
+impl<T> CapacityError<T> {
+    pub fn new(element: T) -> Self {
+        CapacityError { element }
+    }
+
+    pub fn element(self) -> T {
+        self.element
+    }
+}
+
+impl<T> Debug for CapacityError<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "CapacityError: insufficient capacity")
+    }
+}
+
+impl<T> Display for CapacityError<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "insufficient capacity")
+    }
+}
+
+impl<T> Error for CapacityError<T> {}
+
+/// A vector with a fixed capacity of `N` elements stored inline. It never
+/// allocates.
+pub struct ArrayVec<T, const N: usize> {
+    data: [MaybeUninit<T>; N],
+    len: usize,
+}
+
+impl<T, const N: usize> ArrayVec<T, N> {
+    fn ptr(&self) -> *const T {
+        self.data.as_ptr() as *const T
+    }
+
+    fn ptr_mut(&mut self) -> *mut T {
+        self.data.as_mut_ptr() as *mut T
+    }
+
+    pub fn new() -> Self {
+        ArrayVec {
+            data: [const { MaybeUninit::uninit() }; N],
+            len: 0,
+        }
+    }
+
+    pub fn capacity(&self) -> usize {
+        N
+    }
+
+    pub fn remaining_capacity(&self) -> usize {
+        N - self.len
+    }
+
+    pub fn is_full(&self) -> bool {
+        self.len == N
+    }
+
+    pub fn push(&mut self, elem: T) -> Result<(), CapacityError<T>> {
+        if self.is_full() {
+            return Err(CapacityError::new(elem));
+        }
+
+        unsafe {
+            ptr::write(self.ptr_mut().add(self.len), elem);
+        }
+        self.len += 1;
+        Ok(())
+    }
+
+    pub fn pop(&mut self) -> Option<T> {
+        if self.len == 0 {
+            return None;
+        }
+
+        self.len -= 1;
+        unsafe { Some(ptr::read(self.ptr().add(self.len))) }
+    }
+
+    /// Panics if the vec is full; see `try_insert`.
+    pub fn insert(&mut self, index: usize, elem: T) {
+        if self.try_insert(index, elem).is_err() {
+            panic!("insufficient capacity");
+        }
+    }
+
+    pub fn try_insert(&mut self, index: usize, elem: T) -> Result<(), CapacityError<T>> {
+        assert!(index <= self.len, "index out of bounds");
+        if self.is_full() {
+            return Err(CapacityError::new(elem));
+        }
+
+        //shift all the elements from [i .. len] to [i+1 .. len+1] using the old len.
+        unsafe {
+            let ptr = self.ptr_mut();
+            ptr::copy(ptr.add(index), ptr.add(index + 1), self.len - index);
+            ptr::write(ptr.add(index), elem);
+        }
+        self.len += 1;
+        Ok(())
+    }
+
+    pub fn remove(&mut self, index: usize) -> T {
+        assert!(index < self.len, "index out of bounds");
+
+        //shift all the elements from [i+1 .. len + 1] to [i .. len] using the new len.
+        unsafe {
+            self.len -= 1;
+            let ptr = self.ptr_mut();
+            let result = ptr::read(ptr.add(index));
+            ptr::copy(ptr.add(index + 1), ptr.add(index), self.len - index);
+            result
+        }
+    }
+
+    pub fn swap_remove(&mut self, index: usize) -> T {
+        assert!(index < self.len, "index out of bounds");
+
+        //move the last element into the hole left by the removed one.
+        unsafe {
+            self.len -= 1;
+            let ptr = self.ptr_mut();
+            let result = ptr::read(ptr.add(index));
+            ptr::copy(ptr.add(self.len), ptr.add(index), 1);
+            result
+        }
+    }
+
+    pub fn truncate(&mut self, len: usize) {
+        if len >= self.len {
+            return;
+        }
+
+        // Shorten first so a panicking destructor cannot cause a double drop.
+        let tail =
+            ptr::slice_from_raw_parts_mut(unsafe { self.ptr_mut().add(len) }, self.len - len);
+        self.len = len;
+        unsafe { ptr::drop_in_place(tail) };
+    }
+
+    pub fn clear(&mut self) {
+        self.truncate(0)
+    }
+}
+
+impl<T, const N: usize> Default for ArrayVec<T, N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T, const N: usize> Drop for ArrayVec<T, N> {
+    fn drop(&mut self) {
+        unsafe {
+            ptr::drop_in_place(ptr::slice_from_raw_parts_mut(self.ptr_mut(), self.len));
+        }
+    }
+}
+
+impl<T, const N: usize> Deref for ArrayVec<T, N> {
+    type Target = [T];
+    fn deref(&self) -> &[T] {
+        unsafe { std::slice::from_raw_parts(self.ptr(), self.len) }
+    }
+}
+
+impl<T, const N: usize> DerefMut for ArrayVec<T, N> {
+    fn deref_mut(&mut self) -> &mut [T] {
+        unsafe { std::slice::from_raw_parts_mut(self.ptr_mut(), self.len) }
+    }
+}
+
+pub struct IntoIter<T, const N: usize> {
+    data: [MaybeUninit<T>; N],
+    front: usize,
+    back: usize,
+}
+
+impl<T, const N: usize> IntoIter<T, N> {
+    pub fn as_slice(&self) -> &[T] {
+        unsafe {
+            std::slice::from_raw_parts(
+                (self.data.as_ptr() as *const T).add(self.front),
+                self.back - self.front,
+            )
+        }
+    }
+}
+
+impl<T, const N: usize> Iterator for IntoIter<T, N> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        if self.front == self.back {
+            return None;
+        }
+
+        let elem = unsafe { self.data[self.front].assume_init_read() };
+        self.front += 1;
+        Some(elem)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.back - self.front;
+        (len, Some(len))
+    }
+}
+
+impl<T, const N: usize> DoubleEndedIterator for IntoIter<T, N> {
+    fn next_back(&mut self) -> Option<T> {
+        if self.front == self.back {
+            return None;
+        }
+
+        self.back -= 1;
+        unsafe { Some(self.data[self.back].assume_init_read()) }
+    }
+}
+
+impl<T, const N: usize> ExactSizeIterator for IntoIter<T, N> {}
+
+impl<T, const N: usize> Drop for IntoIter<T, N> {
+    fn drop(&mut self) {
+        let remaining = unsafe {
+            ptr::slice_from_raw_parts_mut(
+                (self.data.as_mut_ptr() as *mut T).add(self.front),
+                self.back - self.front,
+            )
+        };
+        unsafe { ptr::drop_in_place(remaining) };
+    }
+}
+
+impl<T, const N: usize> IntoIterator for ArrayVec<T, N> {
+    type Item = T;
+    type IntoIter = IntoIter<T, N>;
+
+    fn into_iter(self) -> IntoIter<T, N> {
+        // Take the storage without running ArrayVec's destructor.
+        let vec = mem::ManuallyDrop::new(self);
+        let data = unsafe { ptr::read(&vec.data) };
+
+        IntoIter {
+            data,
+            front: 0,
+            back: vec.len,
+        }
+    }
+}
+
+impl<'a, T, const N: usize> IntoIterator for &'a ArrayVec<T, N> {
+    type Item = &'a T;
+    type IntoIter = std::slice::Iter<'a, T>;
+
+    fn into_iter(self) -> std::slice::Iter<'a, T> {
+        self.iter()
+    }
+}
+
+impl<'a, T, const N: usize> IntoIterator for &'a mut ArrayVec<T, N> {
+    type Item = &'a mut T;
+    type IntoIter = std::slice::IterMut<'a, T>;
+
+    fn into_iter(self) -> std::slice::IterMut<'a, T> {
+        self.iter_mut()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ArrayVec;
+    use crate::test_util::DropCounter;
+    use std::cell::Cell;
+
+    #[test]
+    fn push_works() {
+        let mut list: ArrayVec<i32, 3> = ArrayVec::new();
+        assert_eq!(list.push(1), Ok(()));
+        assert_eq!(list.push(2), Ok(()));
+        assert_eq!(list.push(3), Ok(()));
+        assert!(list.is_full());
+
+        let err = list.push(4).unwrap_err();
+        assert_eq!(err.element(), 4);
+        assert_eq!(&list[..], &[1, 2, 3]);
+    }
+
+    #[test]
+    fn pop_works() {
+        let mut list: ArrayVec<i32, 3> = ArrayVec::new();
+        assert_eq!(list.pop(), None);
+
+        list.push(1).unwrap();
+        list.push(2).unwrap();
+        assert_eq!(list.pop(), Some(2));
+        assert_eq!(list.remaining_capacity(), 2);
+    }
+
+    #[test]
+    fn insert_works() {
+        let mut list: ArrayVec<i32, 4> = ArrayVec::new();
+        list.push(1).unwrap();
+        list.push(3).unwrap();
+        list.insert(1, 2);
+        assert_eq!(list.try_insert(0, 0), Ok(()));
+        assert_eq!(&list[..], &[0, 1, 2, 3]);
+
+        assert_eq!(list.try_insert(2, 9).unwrap_err().element(), 9);
+        assert_eq!(&list[..], &[0, 1, 2, 3]);
+    }
+
+    #[test]
+    #[should_panic(expected = "insufficient capacity")]
+    fn insert_panics_when_full() {
+        let mut list: ArrayVec<i32, 1> = ArrayVec::new();
+        list.push(1).unwrap();
+        list.insert(0, 0);
+    }
+
+    #[test]
+    fn remove_works() {
+        let mut list: ArrayVec<i32, 4> = ArrayVec::new();
+        for i in 1..=4 {
+            list.push(i).unwrap();
+        }
+
+        assert_eq!(list.remove(1), 2);
+        assert_eq!(&list[..], &[1, 3, 4]);
+        assert_eq!(list.swap_remove(0), 1);
+        assert_eq!(&list[..], &[4, 3]);
+    }
+
+    #[test]
+    fn slice_methods_work() {
+        let mut list: ArrayVec<i32, 4> = ArrayVec::new();
+        for i in [3, 1, 2] {
+            list.push(i).unwrap();
+        }
+
+        list.sort();
+        assert_eq!(list.iter().sum::<i32>(), 6);
+        assert_eq!(list.first(), Some(&1));
+        assert!(list.contains(&3));
+    }
+
+    #[test]
+    fn drops_each_element_once() {
+        let drops = Cell::new(0);
+        let mut list: ArrayVec<DropCounter, 4> = ArrayVec::new();
+        for _ in 0..4 {
+            assert!(list.push(DropCounter::new(&drops)).is_ok());
+        }
+
+        drop(list.push(DropCounter::new(&drops)));
+        assert_eq!(drops.get(), 1);
+
+        list.truncate(3);
+        assert_eq!(drops.get(), 2);
+
+        drop(list);
+        assert_eq!(drops.get(), 5);
+    }
+
+    #[test]
+    fn into_iter_works() {
+        let drops = Cell::new(0);
+        let mut list: ArrayVec<DropCounter, 4> = ArrayVec::new();
+        for _ in 0..4 {
+            assert!(list.push(DropCounter::new(&drops)).is_ok());
+        }
+
+        let mut iter = list.into_iter();
+        assert_eq!(iter.len(), 4);
+        drop(iter.next());
+        drop(iter.next_back());
+        assert_eq!(iter.as_slice().len(), 2);
+        drop(iter);
+        assert_eq!(drops.get(), 4);
+    }
+}
@@ -1,31 +1,58 @@
-use std::fmt::{self, Display, Formatter};
+use std::cmp::Ordering;
+use std::fmt::{self, Debug, Display, Formatter};
+use std::hash::{Hash, Hasher};
 use std::marker::PhantomData;
-use std::ptr::NonNull;
+use std::mem;
+use std::ops::{Index, IndexMut};
+use std::ptr::{self, NonNull};
 
-struct Node<T: Copy + PartialEq> {
+use super::merge_sort::{self, SortNode};
+
+type Link<T> = Option<NonNull<Node<T>>>;
+
+struct Node<T> {
     data: T,
     prev: Option<NonNull<Node<T>>>,
     next: Option<NonNull<Node<T>>>,
 }
 
-impl<T: Copy + PartialEq> Node<T> {
+impl<T> Node<T> {
     fn new(data: T) -> Self {
         Node {
-            data: data,
+            data,
             prev: None,
             next: None,
         }
     }
 }
 
-pub struct DoublyLinkedList<T: Copy + PartialEq> {
+impl<T> SortNode for Node<T> {
+    type Elem = T;
+
+    unsafe fn elem<'a>(node: NonNull<Self>) -> &'a T {
+        &(*node.as_ptr()).data
+    }
+
+    unsafe fn next(node: NonNull<Self>) -> *mut Link<T> {
+        ptr::addr_of_mut!((*node.as_ptr()).next)
+    }
+
+    unsafe fn set_prev(node: NonNull<Self>, prev: Link<T>) {
+        (*node.as_ptr()).prev = prev;
+    }
+}
+
+pub struct DoublyLinkedList<T> {
     len: usize,
     head: Option<NonNull<Node<T>>>,
     tail: Option<NonNull<Node<T>>>,
     marker: PhantomData<Box<Node<T>>>,
 }
 
-impl<T: Copy + PartialEq> DoublyLinkedList<T> {
+unsafe impl<T: Send> Send for DoublyLinkedList<T> {}
+unsafe impl<T: Sync> Sync for DoublyLinkedList<T> {}
+
+impl<T> DoublyLinkedList<T> {
     pub fn new() -> Self {
         Self {
             len: 0,
@@ -79,7 +106,7 @@ impl<T: Copy + PartialEq> DoublyLinkedList<T> {
         }
 
         // Optimization to based on index distance from each end
-        if index + 1 <= self.len / 2 {
+        if index < self.len / 2 {
             let mut curr = self.head;
             let mut count = 0;
 
@@ -87,15 +114,15 @@ impl<T: Copy + PartialEq> DoublyLinkedList<T> {
                 if count == index {
                     let mut node = Box::new(Node::new(data));
 
+                    // index > 0 here, so the node being displaced always has a prev.
                     unsafe {
-                        node.prev = (*curr_ptr.as_ptr()).prev;
+                        let prev_ptr = (*curr_ptr.as_ptr()).prev.unwrap();
+                        node.prev = Some(prev_ptr);
                         node.next = Some(curr_ptr);
 
-                        if let Some(prev_ptr) = (*curr_ptr.as_ptr()).prev {
-                            let node_ptr = NonNull::new(Box::into_raw(node));
-                            (*prev_ptr.as_ptr()).next = node_ptr;
-                            (*curr_ptr.as_ptr()).prev = node_ptr;
-                        }
+                        let node_ptr = NonNull::new(Box::into_raw(node));
+                        (*prev_ptr.as_ptr()).next = node_ptr;
+                        (*curr_ptr.as_ptr()).prev = node_ptr;
                     };
 
                     self.len += 1;
@@ -113,15 +140,15 @@ impl<T: Copy + PartialEq> DoublyLinkedList<T> {
                 if count == index {
                     let mut node = Box::new(Node::new(data));
 
+                    // index > 0 here, so the node being displaced always has a prev.
                     unsafe {
-                        node.prev = (*curr_ptr.as_ptr()).prev;
+                        let prev_ptr = (*curr_ptr.as_ptr()).prev.unwrap();
+                        node.prev = Some(prev_ptr);
                         node.next = Some(curr_ptr);
 
-                        if let Some(prev_ptr) = (*curr_ptr.as_ptr()).prev {
-                            let node_ptr = NonNull::new(Box::into_raw(node));
-                            (*prev_ptr.as_ptr()).next = node_ptr;
-                            (*curr_ptr.as_ptr()).prev = node_ptr;
-                        }
+                        let node_ptr = NonNull::new(Box::into_raw(node));
+                        (*prev_ptr.as_ptr()).next = node_ptr;
+                        (*curr_ptr.as_ptr()).prev = node_ptr;
                     };
 
                     self.len += 1;
@@ -181,7 +208,7 @@ impl<T: Copy + PartialEq> DoublyLinkedList<T> {
             return self.pop_back();
         }
 
-        if index + 1 <= self.len / 2 {
+        if index < self.len / 2 {
             let mut curr = self.head;
             let mut count = 0;
 
@@ -247,7 +274,7 @@ impl<T: Copy + PartialEq> DoublyLinkedList<T> {
             panic!("Index out of bounds")
         }
 
-        if index + 1 <= self.len / 2 {
+        if index < self.len / 2 {
             let mut curr = self.head;
             let mut count = 0;
 
@@ -274,41 +301,578 @@ impl<T: Copy + PartialEq> DoublyLinkedList<T> {
         None
     }
 
-    pub fn contains(&self, data: T) -> bool {
-        let mut curr = self.head;
-        while let Some(curr_ptr) = curr {
-            if unsafe { (*curr_ptr.as_ptr()).data == data } {
-                return true;
-            }
+    pub fn get_mut(&mut self, index: usize) -> Option<&mut T> {
+        if self.len <= index {
+            panic!("Index out of bounds")
+        }
+
+        let len = self.len;
+        if index < len / 2 {
+            self.iter_mut().nth(index)
+        } else {
+            self.iter_mut().nth_back(len - 1 - index)
+        }
+    }
 
-            curr = unsafe { (*curr_ptr.as_ptr()).next };
+    pub fn iter(&self) -> Iter<'_, T> {
+        Iter {
+            head: self.head,
+            tail: self.tail,
+            len: self.len,
+            marker: PhantomData,
         }
+    }
 
-        false
+    pub fn iter_mut(&mut self) -> IterMut<'_, T> {
+        IterMut {
+            head: self.head,
+            tail: self.tail,
+            len: self.len,
+            marker: PhantomData,
+        }
     }
 
-    pub fn collect(&self) -> Vec<T> {
-        let mut result = Vec::with_capacity(self.len);
-        let mut curr = self.head;
-        while let Some(node_ptr) = curr {
-            unsafe {
-                let node = &*node_ptr.as_ptr();
-                result.push(node.data.clone());
-                curr = node.next;
-            }
+    pub fn cursor_front_mut(&mut self) -> CursorMut<'_, T> {
+        CursorMut {
+            index: 0,
+            current: self.head,
+            list: self,
+        }
+    }
+
+    pub fn cursor_back_mut(&mut self) -> CursorMut<'_, T> {
+        CursorMut {
+            index: self.len.saturating_sub(1),
+            current: self.tail,
+            list: self,
+        }
+    }
+
+    // Links the chain `first..=last` of `count` nodes between the adjacent
+    // nodes `prev` and `next`, where None stands for the list ends.
+    unsafe fn splice_nodes(
+        &mut self,
+        prev: Link<T>,
+        next: Link<T>,
+        first: NonNull<Node<T>>,
+        last: NonNull<Node<T>>,
+        count: usize,
+    ) {
+        (*first.as_ptr()).prev = prev;
+        (*last.as_ptr()).next = next;
+
+        match prev {
+            None => self.head = Some(first),
+            Some(prev_ptr) => (*prev_ptr.as_ptr()).next = Some(first),
+        }
+        match next {
+            None => self.tail = Some(last),
+            Some(next_ptr) => (*next_ptr.as_ptr()).prev = Some(last),
+        }
+
+        self.len += count;
+    }
+
+    unsafe fn unlink_node(&mut self, node_ptr: NonNull<Node<T>>) -> Box<Node<T>> {
+        let node = Box::from_raw(node_ptr.as_ptr());
+        match node.prev {
+            None => self.head = node.next,
+            Some(prev_ptr) => (*prev_ptr.as_ptr()).next = node.next,
         }
+        match node.next {
+            None => self.tail = node.prev,
+            Some(next_ptr) => (*next_ptr.as_ptr()).prev = node.prev,
+        }
+
+        self.len -= 1;
+        node
+    }
+
+    // Splits after `node`, which sits at position `at - 1`. None takes the
+    // whole list.
+    unsafe fn split_off_after_node(&mut self, node: Link<T>, at: usize) -> DoublyLinkedList<T> {
+        let Some(node_ptr) = node else {
+            return mem::take(self);
+        };
+
+        let Some(second_head) = (*node_ptr.as_ptr()).next.take() else {
+            return DoublyLinkedList::new();
+        };
+        (*second_head.as_ptr()).prev = None;
+
+        let second = DoublyLinkedList {
+            len: self.len - at,
+            head: Some(second_head),
+            tail: self.tail,
+            marker: PhantomData,
+        };
+        self.tail = Some(node_ptr);
+        self.len = at;
+        second
+    }
+
+    // Splits before `node`, which sits at position `at`. None takes the whole
+    // list.
+    unsafe fn split_off_before_node(&mut self, node: Link<T>, at: usize) -> DoublyLinkedList<T> {
+        let Some(node_ptr) = node else {
+            return mem::take(self);
+        };
+
+        let Some(first_tail) = (*node_ptr.as_ptr()).prev.take() else {
+            return DoublyLinkedList::new();
+        };
+        (*first_tail.as_ptr()).next = None;
+
+        let first = DoublyLinkedList {
+            len: at,
+            head: self.head,
+            tail: Some(first_tail),
+            marker: PhantomData,
+        };
+        self.head = Some(node_ptr);
+        self.len -= at;
+        first
+    }
+}
+
+impl<T> DoublyLinkedList<T> {
+    /// Stable merge sort that relinks the nodes in place, without allocating
+    /// or moving any elements.
+    pub fn sort_by<F>(&mut self, mut compare: F)
+    where
+        F: FnMut(&T, &T) -> Ordering,
+    {
+        unsafe { merge_sort::sort_by(&mut self.head, &mut self.tail, self.len, &mut compare) }
+    }
+
+    pub fn sort_by_key<K, F>(&mut self, mut f: F)
+    where
+        K: Ord,
+        F: FnMut(&T) -> K,
+    {
+        self.sort_by(|a, b| f(a).cmp(&f(b)))
+    }
+}
+
+impl<T: Ord> DoublyLinkedList<T> {
+    pub fn sort(&mut self) {
+        self.sort_by(T::cmp)
+    }
+
+    /// Merges the sorted `other` into this sorted list, leaving `other` empty.
+    /// On ties, elements already in this list come first.
+    pub fn merge(&mut self, other: &mut DoublyLinkedList<T>) {
+        let right = other.head.take();
+        other.tail = None;
+        self.len += mem::take(&mut other.len);
+
+        unsafe { merge_sort::merge(&mut self.head, &mut self.tail, right, &mut T::cmp) }
+    }
+}
+
+impl<T: PartialEq> DoublyLinkedList<T> {
+    pub fn contains(&self, data: &T) -> bool {
+        self.iter().any(|elt| elt == data)
+    }
+}
 
-        result
+impl<T: Clone> DoublyLinkedList<T> {
+    pub fn collect(&self) -> Vec<T> {
+        self.iter().cloned().collect()
     }
 }
 
-impl<T: Copy + PartialEq> Drop for DoublyLinkedList<T> {
+impl<T> Default for DoublyLinkedList<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> Drop for DoublyLinkedList<T> {
     fn drop(&mut self) {
         while self.pop_front().is_some() {}
     }
 }
 
-impl<T: Copy + PartialEq + Display> Display for DoublyLinkedList<T> {
+impl<T> FromIterator<T> for DoublyLinkedList<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut list = DoublyLinkedList::new();
+        list.extend(iter);
+        list
+    }
+}
+
+impl<T> Extend<T> for DoublyLinkedList<T> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for data in iter {
+            self.push_back(data);
+        }
+    }
+}
+
+impl<T: Clone> Clone for DoublyLinkedList<T> {
+    fn clone(&self) -> Self {
+        self.iter().cloned().collect()
+    }
+}
+
+impl<T: Debug> Debug for DoublyLinkedList<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+impl<T: PartialEq> PartialEq for DoublyLinkedList<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.len == other.len && self.iter().eq(other.iter())
+    }
+}
+
+impl<T: Eq> Eq for DoublyLinkedList<T> {}
+
+impl<T: Hash> Hash for DoublyLinkedList<T> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.len.hash(state);
+        for elem in self.iter() {
+            elem.hash(state);
+        }
+    }
+}
+
+impl<T: PartialOrd> PartialOrd for DoublyLinkedList<T> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        self.iter().partial_cmp(other.iter())
+    }
+}
+
+impl<T: Ord> Ord for DoublyLinkedList<T> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.iter().cmp(other.iter())
+    }
+}
+
+impl<T> Index<usize> for DoublyLinkedList<T> {
+    type Output = T;
+
+    fn index(&self, index: usize) -> &T {
+        self.get(index).unwrap()
+    }
+}
+
+impl<T> IndexMut<usize> for DoublyLinkedList<T> {
+    fn index_mut(&mut self, index: usize) -> &mut T {
+        self.get_mut(index).unwrap()
+    }
+}
+
+impl<T> IntoIterator for DoublyLinkedList<T> {
+    type Item = T;
+    type IntoIter = IntoIter<T>;
+
+    fn into_iter(self) -> IntoIter<T> {
+        IntoIter { list: self }
+    }
+}
+
+impl<'a, T> IntoIterator for &'a DoublyLinkedList<T> {
+    type Item = &'a T;
+    type IntoIter = Iter<'a, T>;
+
+    fn into_iter(self) -> Iter<'a, T> {
+        self.iter()
+    }
+}
+
+impl<'a, T> IntoIterator for &'a mut DoublyLinkedList<T> {
+    type Item = &'a mut T;
+    type IntoIter = IterMut<'a, T>;
+
+    fn into_iter(self) -> IterMut<'a, T> {
+        self.iter_mut()
+    }
+}
+
+pub struct Iter<'a, T> {
+    head: Option<NonNull<Node<T>>>,
+    tail: Option<NonNull<Node<T>>>,
+    len: usize,
+    marker: PhantomData<&'a Node<T>>,
+}
+
+unsafe impl<T: Sync> Send for Iter<'_, T> {}
+unsafe impl<T: Sync> Sync for Iter<'_, T> {}
+
+impl<'a, T> Iterator for Iter<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<&'a T> {
+        if self.len == 0 {
+            return None;
+        }
+
+        self.head.map(|node_ptr| unsafe {
+            let node = &*node_ptr.as_ptr();
+            self.head = node.next;
+            self.len -= 1;
+            &node.data
+        })
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.len, Some(self.len))
+    }
+}
+
+impl<'a, T> DoubleEndedIterator for Iter<'a, T> {
+    fn next_back(&mut self) -> Option<&'a T> {
+        if self.len == 0 {
+            return None;
+        }
+
+        self.tail.map(|node_ptr| unsafe {
+            let node = &*node_ptr.as_ptr();
+            self.tail = node.prev;
+            self.len -= 1;
+            &node.data
+        })
+    }
+}
+
+impl<T> ExactSizeIterator for Iter<'_, T> {}
+
+pub struct IterMut<'a, T> {
+    head: Option<NonNull<Node<T>>>,
+    tail: Option<NonNull<Node<T>>>,
+    len: usize,
+    marker: PhantomData<&'a mut Node<T>>,
+}
+
+unsafe impl<T: Send> Send for IterMut<'_, T> {}
+unsafe impl<T: Sync> Sync for IterMut<'_, T> {}
+
+impl<'a, T> Iterator for IterMut<'a, T> {
+    type Item = &'a mut T;
+
+    fn next(&mut self) -> Option<&'a mut T> {
+        if self.len == 0 {
+            return None;
+        }
+
+        self.head.map(|node_ptr| unsafe {
+            let node = &mut *node_ptr.as_ptr();
+            self.head = node.next;
+            self.len -= 1;
+            &mut node.data
+        })
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.len, Some(self.len))
+    }
+}
+
+impl<'a, T> DoubleEndedIterator for IterMut<'a, T> {
+    fn next_back(&mut self) -> Option<&'a mut T> {
+        if self.len == 0 {
+            return None;
+        }
+
+        self.tail.map(|node_ptr| unsafe {
+            let node = &mut *node_ptr.as_ptr();
+            self.tail = node.prev;
+            self.len -= 1;
+            &mut node.data
+        })
+    }
+}
+
+impl<T> ExactSizeIterator for IterMut<'_, T> {}
+
+/// A cursor that can walk the list in both directions and edit it in O(1) at
+/// its position.
+///
+/// Besides the elements, the cursor can rest on a "ghost" position between
+/// the back and the front of the list; `current` returns None there, moving
+/// past either end lands on it, and insertions at the ghost go to the
+/// corresponding end.
+pub struct CursorMut<'a, T> {
+    index: usize,
+    current: Option<NonNull<Node<T>>>,
+    list: &'a mut DoublyLinkedList<T>,
+}
+
+unsafe impl<T: Send> Send for CursorMut<'_, T> {}
+unsafe impl<T: Sync> Sync for CursorMut<'_, T> {}
+
+impl<T> CursorMut<'_, T> {
+    /// Returns None on the ghost position.
+    pub fn index(&self) -> Option<usize> {
+        self.current.map(|_| self.index)
+    }
+
+    pub fn move_next(&mut self) {
+        match self.current {
+            None => {
+                self.current = self.list.head;
+                self.index = 0;
+            }
+            Some(curr_ptr) => unsafe {
+                self.current = (*curr_ptr.as_ptr()).next;
+                self.index += 1;
+            },
+        }
+    }
+
+    pub fn move_prev(&mut self) {
+        match self.current {
+            None => {
+                self.current = self.list.tail;
+                self.index = self.list.len.saturating_sub(1);
+            }
+            Some(curr_ptr) => unsafe {
+                self.current = (*curr_ptr.as_ptr()).prev;
+                self.index = self.index.checked_sub(1).unwrap_or(self.list.len);
+            },
+        }
+    }
+
+    pub fn current(&mut self) -> Option<&mut T> {
+        self.current
+            .map(|curr_ptr| unsafe { &mut (*curr_ptr.as_ptr()).data })
+    }
+
+    pub fn peek_next(&mut self) -> Option<&mut T> {
+        let next = match self.current {
+            None => self.list.head,
+            Some(curr_ptr) => unsafe { (*curr_ptr.as_ptr()).next },
+        };
+        next.map(|next_ptr| unsafe { &mut (*next_ptr.as_ptr()).data })
+    }
+
+    pub fn peek_prev(&mut self) -> Option<&mut T> {
+        let prev = match self.current {
+            None => self.list.tail,
+            Some(curr_ptr) => unsafe { (*curr_ptr.as_ptr()).prev },
+        };
+        prev.map(|prev_ptr| unsafe { &mut (*prev_ptr.as_ptr()).data })
+    }
+
+    // The neighbours that new nodes go between when inserted after the cursor.
+    fn after(&self) -> (Link<T>, Link<T>) {
+        match self.current {
+            None => (None, self.list.head),
+            Some(curr_ptr) => (Some(curr_ptr), unsafe { (*curr_ptr.as_ptr()).next }),
+        }
+    }
+
+    fn before(&self) -> (Link<T>, Link<T>) {
+        match self.current {
+            None => (self.list.tail, None),
+            Some(curr_ptr) => (unsafe { (*curr_ptr.as_ptr()).prev }, Some(curr_ptr)),
+        }
+    }
+
+    /// Inserts after the current element, or at the front on the ghost.
+    pub fn insert_after(&mut self, data: T) {
+        let node_ptr = NonNull::from(Box::leak(Box::new(Node::new(data))));
+        let (prev, next) = self.after();
+        unsafe { self.list.splice_nodes(prev, next, node_ptr, node_ptr, 1) };
+
+        if self.current.is_none() {
+            self.index = self.list.len;
+        }
+    }
+
+    /// Inserts before the current element, or at the back on the ghost.
+    pub fn insert_before(&mut self, data: T) {
+        let node_ptr = NonNull::from(Box::leak(Box::new(Node::new(data))));
+        let (prev, next) = self.before();
+        unsafe { self.list.splice_nodes(prev, next, node_ptr, node_ptr, 1) };
+        self.index += 1;
+    }
+
+    /// Removes the current element and moves the cursor to the next one.
+    pub fn remove_current(&mut self) -> Option<T> {
+        let curr_ptr = self.current?;
+        unsafe {
+            self.current = (*curr_ptr.as_ptr()).next;
+            Some(self.list.unlink_node(curr_ptr).data)
+        }
+    }
+
+    /// Returns everything after the cursor, or the whole list on the ghost.
+    pub fn split_after(&mut self) -> DoublyLinkedList<T> {
+        let at = if self.current.is_some() {
+            self.index + 1
+        } else {
+            self.index = 0;
+            0
+        };
+        unsafe { self.list.split_off_after_node(self.current, at) }
+    }
+
+    /// Returns everything before the cursor, or the whole list on the ghost.
+    pub fn split_before(&mut self) -> DoublyLinkedList<T> {
+        let at = self.index;
+        self.index = 0;
+        unsafe { self.list.split_off_before_node(self.current, at) }
+    }
+
+    /// Moves all of `other` in after the current element, or at the front on
+    /// the ghost.
+    pub fn splice_after(&mut self, mut other: DoublyLinkedList<T>) {
+        let (Some(first), Some(last)) = (other.head.take(), other.tail.take()) else {
+            return;
+        };
+        let count = mem::replace(&mut other.len, 0);
+
+        let (prev, next) = self.after();
+        unsafe { self.list.splice_nodes(prev, next, first, last, count) };
+
+        if self.current.is_none() {
+            self.index = self.list.len;
+        }
+    }
+
+    /// Moves all of `other` in before the current element, or at the back on
+    /// the ghost.
+    pub fn splice_before(&mut self, mut other: DoublyLinkedList<T>) {
+        let (Some(first), Some(last)) = (other.head.take(), other.tail.take()) else {
+            return;
+        };
+        let count = mem::replace(&mut other.len, 0);
+
+        let (prev, next) = self.before();
+        unsafe { self.list.splice_nodes(prev, next, first, last, count) };
+        self.index += count;
+    }
+}
+
+pub struct IntoIter<T> {
+    list: DoublyLinkedList<T>,
+}
+
+impl<T> Iterator for IntoIter<T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        self.list.pop_front()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.list.len, Some(self.list.len))
+    }
+}
+
+impl<T> DoubleEndedIterator for IntoIter<T> {
+    fn next_back(&mut self) -> Option<T> {
+        self.list.pop_back()
+    }
+}
+
+impl<T> ExactSizeIterator for IntoIter<T> {}
+
+impl<T: Display> Display for DoublyLinkedList<T> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self.head {
             Some(node) => write!(f, "{}", unsafe { node.as_ref() }),
@@ -317,7 +881,7 @@ impl<T: Copy + PartialEq + Display> Display for DoublyLinkedList<T> {
     }
 }
 
-impl<T: Copy + PartialEq + Display> Display for Node<T> {
+impl<T: Display> Display for Node<T> {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
         match self.next {
             Some(node) => write!(f, "{} {}", self.data, unsafe { node.as_ref() }),
@@ -326,9 +890,69 @@ impl<T: Copy + PartialEq + Display> Display for Node<T> {
     }
 }
 
+/// Compile-time checks on variance and thread safety.
+///
+/// `IterMut` must not be covariant, or a `&'static str` slot could be
+/// overwritten through it with a shorter-lived reference:
+///
+/// ```compile_fail
+/// use dust::lists::doubly_linked_list::IterMut;
+///
+/// fn shorten<'a>(iter: IterMut<'a, &'static str>) -> IterMut<'a, &'a str> {
+///     iter
+/// }
+/// ```
+///
+/// A list of `Rc`s must not be sent to another thread:
+///
+/// ```compile_fail
+/// use dust::lists::DoublyLinkedList;
+/// use std::rc::Rc;
+///
+/// let list: DoublyLinkedList<Rc<i32>> = DoublyLinkedList::new();
+/// std::thread::spawn(move || drop(list));
+/// ```
+///
+/// Nor may an iterator over `Cell`s, since it hands out shared references:
+///
+/// ```compile_fail
+/// use dust::lists::DoublyLinkedList;
+/// use std::cell::Cell;
+///
+/// let list: DoublyLinkedList<Cell<i32>> = DoublyLinkedList::new();
+/// let iter = list.iter();
+/// std::thread::scope(|s| {
+///     s.spawn(move || drop(iter));
+/// });
+/// ```
+///
+/// The same goes for `CursorMut`, which can insert through its borrow:
+///
+/// ```compile_fail
+/// use dust::lists::doubly_linked_list::CursorMut;
+///
+/// fn shorten<'a>(cursor: CursorMut<'a, &'static str>) -> CursorMut<'a, &'a str> {
+///     cursor
+/// }
+/// ```
+#[allow(dead_code)]
+fn assert_covariance() {
+    fn list<'a>(x: DoublyLinkedList<&'static str>) -> DoublyLinkedList<&'a str> {
+        x
+    }
+    fn iter<'i, 'a>(x: Iter<'i, &'static str>) -> Iter<'i, &'a str> {
+        x
+    }
+    fn into_iter<'a>(x: IntoIter<&'static str>) -> IntoIter<&'a str> {
+        x
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::DoublyLinkedList;
+    use crate::test_util::{hash_of, DropCounter};
+    use std::cell::Cell;
 
     #[test]
     fn push_front_works() {
@@ -528,10 +1152,295 @@ mod tests {
         list.push_back(2);
         list.push_back(3);
 
-        assert!(list.contains(2));
-        assert!(!list.contains(5));
+        assert!(list.contains(&2));
+        assert!(!list.contains(&5));
 
         let vec = list.collect();
         assert_eq!(vec, vec![1, 2, 3])
     }
+
+    #[test]
+    fn owned_values_work() {
+        let mut list = DoublyLinkedList::new();
+        list.push_back(String::from("b"));
+        list.push_front(String::from("a"));
+        list.push_back(String::from("d"));
+        list.insert(2, String::from("c"));
+
+        assert!(list.contains(&String::from("c")));
+        assert_eq!(list.remove(1), Some(String::from("b")));
+        assert_eq!(list.pop_front(), Some(String::from("a")));
+        assert_eq!(list.pop_back(), Some(String::from("d")));
+        assert_eq!(list.collect(), vec![String::from("c")]);
+    }
+
+    #[test]
+    fn boxed_values_are_dropped() {
+        let drops = Cell::new(0);
+        let mut list = DoublyLinkedList::new();
+        for i in 0..5 {
+            list.push_back(Box::new((i, DropCounter::new(&drops))));
+        }
+
+        assert_eq!(list.remove(2).map(|elt| elt.0), Some(2));
+        assert_eq!(list.len(), 4);
+        assert_eq!(drops.get(), 1);
+
+        list.pop_front();
+        assert_eq!(drops.get(), 2);
+
+        drop(list);
+        assert_eq!(drops.get(), 5);
+    }
+
+    #[test]
+    fn iter_works() {
+        let mut list: DoublyLinkedList<i32> = (1..=4).collect();
+
+        for elt in list.iter_mut() {
+            *elt *= 10;
+        }
+
+        let mut iter = list.iter();
+        assert_eq!(iter.len(), 4);
+        assert_eq!(iter.next(), Some(&10));
+        assert_eq!(iter.next_back(), Some(&40));
+        assert_eq!(iter.next(), Some(&20));
+        assert_eq!(iter.next_back(), Some(&30));
+        assert_eq!(iter.next(), None);
+        assert_eq!(iter.next_back(), None);
+
+        assert_eq!(
+            list.iter().rev().copied().collect::<Vec<_>>(),
+            vec![40, 30, 20, 10]
+        );
+    }
+
+    #[test]
+    fn into_iter_works() {
+        let list: DoublyLinkedList<String> =
+            ["a", "b", "c", "d"].iter().map(|s| s.to_string()).collect();
+
+        let mut iter = list.into_iter();
+        assert_eq!(iter.next(), Some(String::from("a")));
+        assert_eq!(iter.next_back(), Some(String::from("d")));
+        assert_eq!(iter.len(), 2);
+        assert_eq!(iter.collect::<Vec<_>>(), vec!["b", "c"]);
+    }
+
+    #[test]
+    fn extend_and_for_loops_work() {
+        let mut list = DoublyLinkedList::new();
+        list.extend([1, 2]);
+        list.extend(vec![3, 4]);
+
+        for elt in &mut list {
+            *elt += 1;
+        }
+
+        let mut seen = Vec::new();
+        for elt in &list {
+            seen.push(*elt);
+        }
+        assert_eq!(seen, vec![2, 3, 4, 5]);
+    }
+
+    #[test]
+    fn cursor_moves_through_ghost() {
+        let mut list: DoublyLinkedList<i32> = (1..=3).collect();
+        let mut cursor = list.cursor_front_mut();
+        assert_eq!(cursor.index(), Some(0));
+        assert_eq!(cursor.current(), Some(&mut 1));
+
+        cursor.move_prev();
+        assert_eq!(cursor.index(), None);
+        assert_eq!(cursor.current(), None);
+        assert_eq!(cursor.peek_next(), Some(&mut 1));
+        assert_eq!(cursor.peek_prev(), Some(&mut 3));
+
+        cursor.move_prev();
+        assert_eq!(cursor.index(), Some(2));
+        assert_eq!(cursor.current(), Some(&mut 3));
+
+        cursor.move_next();
+        cursor.move_next();
+        assert_eq!(cursor.index(), Some(0));
+        assert_eq!(cursor.peek_prev(), None);
+    }
+
+    #[test]
+    fn cursor_insert_works() {
+        let mut list: DoublyLinkedList<i32> = vec![1, 3].into_iter().collect();
+        let mut cursor = list.cursor_front_mut();
+        cursor.insert_after(2);
+        cursor.insert_before(0);
+        assert_eq!(cursor.index(), Some(1));
+        assert_eq!(cursor.current(), Some(&mut 1));
+
+        cursor.move_prev();
+        cursor.move_prev();
+        cursor.insert_after(-1);
+        cursor.insert_before(4);
+        assert_eq!(cursor.index(), None);
+
+        assert_eq!(list.collect(), vec![-1, 0, 1, 2, 3, 4]);
+        assert_eq!(list.len(), 6);
+    }
+
+    #[test]
+    fn cursor_remove_current_works() {
+        let mut list: DoublyLinkedList<String> =
+            ["a", "b", "c"].iter().map(|s| s.to_string()).collect();
+        let mut cursor = list.cursor_front_mut();
+        cursor.move_next();
+
+        assert_eq!(cursor.remove_current(), Some(String::from("b")));
+        assert_eq!(cursor.current().map(|s| s.as_str()), Some("c"));
+        assert_eq!(cursor.remove_current(), Some(String::from("c")));
+        assert_eq!(cursor.index(), None);
+        assert_eq!(cursor.remove_current(), None);
+
+        cursor.move_next();
+        assert_eq!(cursor.remove_current(), Some(String::from("a")));
+        assert!(list.is_empty());
+        assert_eq!(list.iter().next_back(), None);
+    }
+
+    #[test]
+    fn cursor_split_works() {
+        let mut list: DoublyLinkedList<i32> = (0..6).collect();
+        let mut cursor = list.cursor_front_mut();
+        cursor.move_next();
+        cursor.move_next();
+
+        let after = cursor.split_after();
+        assert_eq!(after.collect(), vec![3, 4, 5]);
+        let before = cursor.split_before();
+        assert_eq!(before.collect(), vec![0, 1]);
+        assert_eq!(cursor.index(), Some(0));
+        assert_eq!(list.collect(), vec![2]);
+        assert_eq!(list.len(), 1);
+
+        let mut cursor = list.cursor_back_mut();
+        cursor.move_next();
+        assert_eq!(cursor.split_after().collect(), vec![2]);
+        assert!(list.is_empty());
+    }
+
+    #[test]
+    fn cursor_splice_works() {
+        let mut list: DoublyLinkedList<i32> = vec![1, 5].into_iter().collect();
+        let mut cursor = list.cursor_front_mut();
+        cursor.splice_after((2..5).collect());
+        assert_eq!(cursor.current(), Some(&mut 1));
+        assert_eq!(cursor.peek_next(), Some(&mut 2));
+
+        cursor.move_prev();
+        cursor.splice_after(vec![-1, 0].into_iter().collect());
+        cursor.splice_before(vec![6, 7].into_iter().collect());
+        cursor.splice_before(DoublyLinkedList::new());
+
+        assert_eq!(list.collect(), vec![-1, 0, 1, 2, 3, 4, 5, 6, 7]);
+        assert_eq!(list.len(), 9);
+        assert_eq!(list.iter().rev().count(), 9);
+    }
+
+    #[test]
+    fn sort_works() {
+        let mut list: DoublyLinkedList<i32> = vec![5, 3, 9, 1, 1, 8, 2, 7, 0].into_iter().collect();
+        list.sort();
+        assert_eq!(list.collect(), vec![0, 1, 1, 2, 3, 5, 7, 8, 9]);
+        assert_eq!(
+            list.iter().rev().copied().collect::<Vec<_>>(),
+            vec![9, 8, 7, 5, 3, 2, 1, 1, 0]
+        );
+
+        list.sort_by(|a, b| b.cmp(a));
+        assert_eq!(list.collect(), vec![9, 8, 7, 5, 3, 2, 1, 1, 0]);
+        assert_eq!(list.pop_back(), Some(0));
+        assert_eq!(list.pop_front(), Some(9));
+    }
+
+    #[test]
+    fn sort_by_key_is_stable() {
+        let mut list: DoublyLinkedList<(i32, char)> =
+            vec![(2, 'a'), (1, 'b'), (2, 'c'), (1, 'd'), (0, 'e')]
+                .into_iter()
+                .collect();
+        list.sort_by_key(|&(key, _)| key);
+
+        assert_eq!(
+            list.collect(),
+            vec![(0, 'e'), (1, 'b'), (1, 'd'), (2, 'a'), (2, 'c')]
+        );
+    }
+
+    #[test]
+    fn sort_keeps_every_node_when_compare_panics() {
+        let mut list: DoublyLinkedList<String> = (0..20).map(|i| (20 - i).to_string()).collect();
+        let mut calls = 0;
+
+        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            list.sort_by(|a, b| {
+                calls += 1;
+                if calls == 25 {
+                    panic!("compare panicked");
+                }
+                a.cmp(b)
+            })
+        }));
+
+        assert!(result.is_err());
+        assert_eq!(list.iter().count(), 20);
+        assert_eq!(list.iter().rev().count(), 20);
+    }
+
+    #[test]
+    fn merge_works() {
+        let mut list: DoublyLinkedList<i32> = vec![1, 4, 6, 9].into_iter().collect();
+        let mut other: DoublyLinkedList<i32> = vec![2, 4, 10].into_iter().collect();
+
+        list.merge(&mut other);
+        assert_eq!(list.collect(), vec![1, 2, 4, 4, 6, 9, 10]);
+        assert_eq!(list.iter().next_back(), Some(&10));
+        assert_eq!(list.len(), 7);
+        assert!(other.is_empty());
+    }
+
+    #[test]
+    fn indexes_from_the_nearer_end() {
+        let mut list: DoublyLinkedList<i32> = (0..10).collect();
+        list[1] = -1;
+        list[8] = -8;
+        assert_eq!(list[1], -1);
+        assert_eq!(list[8], -8);
+        assert_eq!(list.iter().nth(8), Some(&-8));
+        assert_eq!(list.iter().rev().nth(1), Some(&-8));
+
+        let std_list: std::collections::LinkedList<i32> = list.iter().copied().collect();
+        assert_eq!(hash_of(&list), hash_of(&std_list));
+        assert_eq!(format!("{:?}", list), format!("{:?}", std_list));
+
+        let original: DoublyLinkedList<i32> = (0..10).collect();
+        assert!(list < original);
+    }
+
+    #[test]
+    fn send_and_sync_work() {
+        let mut list: DoublyLinkedList<String> = ["a", "b"].iter().map(|s| s.to_string()).collect();
+        list = std::thread::spawn(move || {
+            list.push_back("c".to_string());
+            list
+        })
+        .join()
+        .unwrap();
+
+        let list = &list;
+        std::thread::scope(|s| {
+            let lens = s.spawn(move || list.iter().map(String::len).sum::<usize>());
+            let joined = s.spawn(move || list.iter().cloned().collect::<String>());
+            assert_eq!(lens.join().unwrap(), 3);
+            assert_eq!(joined.join().unwrap(), "abc");
+        });
+    }
 }
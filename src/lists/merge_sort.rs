@@ -0,0 +1,153 @@
+use std::cmp::Ordering;
+use std::ptr::NonNull;
+
+type Link<N> = Option<NonNull<N>>;
+
+// The in-place merge sort behind `sort_by` and `merge` on the linked lists.
+// Both lists relink their `next` pointers the same way; a doubly linked node
+// additionally rebuilds its `prev` pointer through `set_prev`.
+pub(crate) trait SortNode: Sized {
+    type Elem;
+
+    // Each of these requires `node` to point to a live node.
+    unsafe fn elem<'a>(node: NonNull<Self>) -> &'a Self::Elem;
+
+    unsafe fn next(node: NonNull<Self>) -> *mut Link<Self>;
+
+    unsafe fn set_prev(_node: NonNull<Self>, _prev: Link<Self>) {}
+}
+
+// Stable merge sort of the `len` nodes owned by the list from `head` to `tail`.
+pub(crate) unsafe fn sort_by<N, F>(
+    head: &mut Link<N>,
+    tail: &mut Link<N>,
+    len: usize,
+    compare: &mut F,
+) where
+    N: SortNode,
+    F: FnMut(&N::Elem, &N::Elem) -> Ordering,
+{
+    let mut width = 1;
+    while width < len {
+        let mut pass = MergePass::new(head, tail);
+        while pass.rest.is_some() {
+            pass.left = pass.rest.take();
+            pass.right = cut(pass.left, width);
+            pass.rest = cut(pass.right, width);
+            pass.merge(compare);
+        }
+        width *= 2;
+    }
+}
+
+// Merges the sorted chain starting at `right`, whose nodes the list takes
+// over, into the sorted list from `head` to `tail`. On ties, nodes already
+// in the list come first.
+pub(crate) unsafe fn merge<N, F>(
+    head: &mut Link<N>,
+    tail: &mut Link<N>,
+    right: Link<N>,
+    compare: &mut F,
+) where
+    N: SortNode,
+    F: FnMut(&N::Elem, &N::Elem) -> Ordering,
+{
+    let mut pass = MergePass::new(head, tail);
+    pass.left = pass.rest.take();
+    pass.right = right;
+    pass.merge(compare);
+}
+
+// Detaches the chain after its first `n` nodes and returns the remainder.
+// Only `next` links are cut; `prev` links are rebuilt as nodes are relinked.
+unsafe fn cut<N: SortNode>(chain: Link<N>, n: usize) -> Link<N> {
+    let mut last = chain?;
+    for _ in 1..n {
+        match *N::next(last) {
+            Some(next_ptr) => last = next_ptr,
+            None => return None,
+        }
+    }
+    (*N::next(last)).take()
+}
+
+// Owns the nodes while a merge pass has the list torn apart into chains.
+// Dropping it links whatever is left back into the list, so a panicking
+// comparator neither leaks nodes nor leaves the list dangling.
+struct MergePass<'a, N: SortNode> {
+    head: &'a mut Link<N>,
+    list_tail: &'a mut Link<N>,
+    tail: Link<N>,
+    left: Link<N>,
+    right: Link<N>,
+    rest: Link<N>,
+}
+
+impl<'a, N: SortNode> MergePass<'a, N> {
+    fn new(head: &'a mut Link<N>, list_tail: &'a mut Link<N>) -> Self {
+        let rest = head.take();
+        *list_tail = None;
+
+        MergePass {
+            head,
+            list_tail,
+            tail: None,
+            left: None,
+            right: None,
+            rest,
+        }
+    }
+
+    fn push_chain(&mut self, chain: Link<N>) {
+        let Some(first) = chain else {
+            return;
+        };
+
+        unsafe {
+            match self.tail {
+                None => *self.head = Some(first),
+                Some(tail_ptr) => *N::next(tail_ptr) = Some(first),
+            }
+            N::set_prev(first, self.tail);
+
+            let mut last = first;
+            while let Some(next_ptr) = *N::next(last) {
+                N::set_prev(next_ptr, Some(last));
+                last = next_ptr;
+            }
+            self.tail = Some(last);
+        }
+    }
+
+    fn merge<F>(&mut self, compare: &mut F)
+    where
+        F: FnMut(&N::Elem, &N::Elem) -> Ordering,
+    {
+        while let (Some(left_ptr), Some(right_ptr)) = (self.left, self.right) {
+            unsafe {
+                let node = if compare(N::elem(right_ptr), N::elem(left_ptr)) == Ordering::Less {
+                    self.right = (*N::next(right_ptr)).take();
+                    right_ptr
+                } else {
+                    self.left = (*N::next(left_ptr)).take();
+                    left_ptr
+                };
+                self.push_chain(Some(node));
+            }
+        }
+
+        let left = self.left.take();
+        self.push_chain(left);
+        let right = self.right.take();
+        self.push_chain(right);
+    }
+}
+
+impl<N: SortNode> Drop for MergePass<'_, N> {
+    fn drop(&mut self) {
+        for chain in [self.left.take(), self.right.take(), self.rest.take()] {
+            self.push_chain(chain);
+        }
+        *self.list_tail = self.tail;
+    }
+}
@@ -1,7 +1,15 @@
-mod doubly_linked_list;
-mod singly_linked_list;
-mod vec;
+pub mod array_vec;
+pub mod doubly_linked_list;
+pub(crate) mod merge_sort;
+pub(crate) mod node_list;
+pub(crate) mod raw_vec;
+pub(crate) mod slice_ops;
+pub mod singly_linked_list;
+pub mod small_vec;
+pub mod vec;
 
+pub use self::array_vec::{ArrayVec, CapacityError};
 pub use self::doubly_linked_list::DoublyLinkedList;
 pub use self::singly_linked_list::SinglyLinkedList;
-pub use self::vec::Vec;
+pub use self::small_vec::SmallVec;
+pub use self::vec::{GrowthStrategy, Vec};
@@ -0,0 +1,97 @@
+use std::marker::PhantomData;
+use std::ptr::NonNull;
+
+pub(crate) struct Links<N> {
+    pub(crate) prev: Option<NonNull<N>>,
+    pub(crate) next: Option<NonNull<N>>,
+}
+
+impl<N> Links<N> {
+    pub(crate) fn new() -> Self {
+        Links {
+            prev: None,
+            next: None,
+        }
+    }
+}
+
+// Node types embed a `Links` and say where it is. Relinking goes through raw
+// pointers to the links alone, never `&mut` to the whole node, so pointers
+// into the rest of the node (like a `KeyRef` into its key) stay valid.
+pub(crate) trait Linked: Sized {
+    // `node` must point to a live node.
+    unsafe fn links(node: *mut Self) -> *mut Links<Self>;
+}
+
+// An intrusive doubly linked list of boxed nodes for structures that also
+// index the nodes from elsewhere, such as `LinkedHashMap`. The list owns the
+// nodes linked into it and frees them when dropped.
+pub(crate) struct NodeList<N: Linked> {
+    head: Option<NonNull<N>>,
+    tail: Option<NonNull<N>>,
+    marker: PhantomData<Box<N>>,
+}
+
+impl<N: Linked> NodeList<N> {
+    pub(crate) fn new() -> Self {
+        NodeList {
+            head: None,
+            tail: None,
+            marker: PhantomData,
+        }
+    }
+
+    pub(crate) fn head(&self) -> Option<NonNull<N>> {
+        self.head
+    }
+
+    pub(crate) fn tail(&self) -> Option<NonNull<N>> {
+        self.tail
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        self.head.is_none()
+    }
+
+    // Takes ownership of `node_ptr`, which must come from `Box::into_raw` and
+    // not be linked into any list.
+    pub(crate) unsafe fn link_back(&mut self, node_ptr: NonNull<N>) {
+        let links = N::links(node_ptr.as_ptr());
+        (*links).prev = self.tail;
+        (*links).next = None;
+        match self.tail {
+            None => self.head = Some(node_ptr),
+            Some(tail_ptr) => (*N::links(tail_ptr.as_ptr())).next = Some(node_ptr),
+        }
+        self.tail = Some(node_ptr);
+    }
+
+    // Hands ownership of `node_ptr`, which must be linked into this list,
+    // back to the caller.
+    pub(crate) unsafe fn unlink(&mut self, node_ptr: NonNull<N>) {
+        let links = N::links(node_ptr.as_ptr());
+        match (*links).prev {
+            None => self.head = (*links).next,
+            Some(prev_ptr) => (*N::links(prev_ptr.as_ptr())).next = (*links).next,
+        }
+        match (*links).next {
+            None => self.tail = (*links).prev,
+            Some(next_ptr) => (*N::links(next_ptr.as_ptr())).prev = (*links).prev,
+        }
+        (*links).prev = None;
+        (*links).next = None;
+    }
+}
+
+impl<N: Linked> Drop for NodeList<N> {
+    fn drop(&mut self) {
+        let mut curr = self.head.take();
+        while let Some(node_ptr) = curr {
+            unsafe {
+                curr = (*N::links(node_ptr.as_ptr())).next;
+                drop(Box::from_raw(node_ptr.as_ptr()));
+            }
+        }
+        self.tail = None;
+    }
+}
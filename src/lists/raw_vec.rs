@@ -0,0 +1,157 @@
+use crate::allocator::{Allocator, Global, TryReserveError};
+use std::alloc::{self, Layout};
+use std::mem;
+use std::ptr::NonNull;
+
+/// How a `Vec` picks its next capacity when a push runs out of room.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum GrowthStrategy {
+    #[default]
+    Doubling,
+    /// Grows by half the current capacity.
+    OneAndHalf,
+    /// Grows by a fixed number of elements. `Chunk(0)` grows only as far as
+    /// each push or reserve needs.
+    Chunk(usize),
+}
+
+pub(crate) struct RawVec<T, A: Allocator = Global> {
+    pub(crate) ptr: NonNull<T>,
+    pub(crate) cap: usize,
+    pub(crate) growth: GrowthStrategy,
+    pub(crate) alloc: A,
+}
+
+// The buffer is uniquely owned, so it is as thread-safe as its elements and
+// allocator.
+unsafe impl<T: Send, A: Allocator + Send> Send for RawVec<T, A> {}
+unsafe impl<T: Sync, A: Allocator + Sync> Sync for RawVec<T, A> {}
+
+impl<T, A: Allocator> RawVec<T, A> {
+    // Tiny elements skip the 1, 2, 4 steps, like std: a handful of reallocations
+    // for a few bytes each is pure overhead.
+    const MIN_NON_ZERO_CAP: usize = if mem::size_of::<T>() == 1 {
+        8
+    } else if mem::size_of::<T>() <= 1024 {
+        4
+    } else {
+        1
+    };
+
+    pub(crate) fn new_in(alloc: A) -> Self {
+        let cap = if mem::size_of::<T>() == 0 {
+            usize::MAX
+        } else {
+            0
+        };
+
+        RawVec {
+            ptr: NonNull::dangling(),
+            cap,
+            growth: GrowthStrategy::default(),
+            alloc,
+        }
+    }
+
+    pub(crate) fn with_capacity_in(cap: usize, alloc: A) -> Self {
+        let mut buffer = RawVec::new_in(alloc);
+        if mem::size_of::<T>() != 0 && cap > 0 {
+            buffer.reallocate(cap);
+        }
+        buffer
+    }
+
+    pub(crate) fn grow(&mut self) {
+        handle_reserve(self.grow_amortized(self.cap, 1))
+    }
+
+    // Makes room for `additional` more elements past `len`, stepping the
+    // capacity by the growth strategy so repeated pushes stay amortized O(1).
+    pub(crate) fn grow_amortized(
+        &mut self,
+        len: usize,
+        additional: usize,
+    ) -> Result<(), TryReserveError> {
+        let needed = len
+            .checked_add(additional)
+            .ok_or(TryReserveError::CapacityOverflow)?;
+        if needed <= self.cap {
+            return Ok(());
+        }
+
+        let stepped = match self.growth {
+            GrowthStrategy::Doubling => self.cap.saturating_mul(2),
+            GrowthStrategy::OneAndHalf => self.cap.saturating_add(self.cap / 2),
+            GrowthStrategy::Chunk(chunk) => self.cap.saturating_add(chunk),
+        };
+        self.try_reallocate(stepped.max(needed).max(Self::MIN_NON_ZERO_CAP))
+    }
+
+    pub(crate) fn reallocate(&mut self, new_cap: usize) {
+        handle_reserve(self.try_reallocate(new_cap))
+    }
+
+    // Moves the buffer to an allocation of exactly `new_cap` elements, leaving
+    // it untouched if the allocator fails.
+    pub(crate) fn try_reallocate(&mut self, new_cap: usize) -> Result<(), TryReserveError> {
+        if mem::size_of::<T>() == 0 {
+            return Err(TryReserveError::CapacityOverflow);
+        }
+
+        if new_cap == 0 {
+            if self.cap != 0 {
+                unsafe {
+                    self.alloc
+                        .deallocate(self.ptr.cast(), Layout::array::<T>(self.cap).unwrap());
+                }
+            }
+            self.ptr = NonNull::dangling();
+            self.cap = 0;
+            return Ok(());
+        }
+
+        let new_layout =
+            Layout::array::<T>(new_cap).map_err(|_| TryReserveError::CapacityOverflow)?;
+
+        let new_ptr = if self.cap == 0 {
+            self.alloc.allocate(new_layout)
+        } else {
+            let old_layout = Layout::array::<T>(self.cap).unwrap();
+            unsafe {
+                if new_cap > self.cap {
+                    self.alloc.grow(self.ptr.cast(), old_layout, new_layout)
+                } else {
+                    self.alloc.shrink(self.ptr.cast(), old_layout, new_layout)
+                }
+            }
+        };
+
+        self.ptr = new_ptr
+            .map_err(|_| TryReserveError::AllocError { layout: new_layout })?
+            .cast();
+        self.cap = new_cap;
+        Ok(())
+    }
+}
+
+impl<T, A: Allocator> Drop for RawVec<T, A> {
+    fn drop(&mut self) {
+        let elem_size = mem::size_of::<T>();
+
+        if self.cap != 0 && elem_size != 0 {
+            unsafe {
+                self.alloc
+                    .deallocate(self.ptr.cast(), Layout::array::<T>(self.cap).unwrap());
+            }
+        }
+    }
+}
+
+// The infallible paths abort the same way std's collections do.
+pub(crate) fn handle_reserve(result: Result<(), TryReserveError>) {
+    match result {
+        Ok(()) => {}
+        Err(TryReserveError::CapacityOverflow) => panic!("capacity overflow"),
+        Err(TryReserveError::AllocError { layout }) => alloc::handle_alloc_error(layout),
+    }
+}
@@ -1,32 +1,56 @@
-use std::fmt::{self, Display, Formatter};
+use std::cmp::Ordering;
+use std::fmt::{self, Debug, Display, Formatter};
+use std::hash::{Hash, Hasher};
 use std::marker::PhantomData;
-use std::ptr::NonNull;
+use std::mem;
+use std::ops::{Index, IndexMut};
+use std::ptr::{self, NonNull};
 
-struct Node<T: Copy + PartialEq> {
+use super::merge_sort::{self, SortNode};
+
+type Link<T> = Option<NonNull<Node<T>>>;
+
+struct Node<T> {
     data: T,
     next: Option<NonNull<Node<T>>>,
 }
 
-impl<T: Copy + PartialEq> Node<T> {
+impl<T> Node<T> {
     fn new(data: T) -> Self {
-        Node {
-            data: data,
-            next: None,
-        }
+        Node { data, next: None }
+    }
+}
+
+impl<T> SortNode for Node<T> {
+    type Elem = T;
+
+    unsafe fn elem<'a>(node: NonNull<Self>) -> &'a T {
+        &(*node.as_ptr()).data
+    }
+
+    unsafe fn next(node: NonNull<Self>) -> *mut Link<T> {
+        ptr::addr_of_mut!((*node.as_ptr()).next)
     }
 }
 
-pub struct SinglyLinkedList<T: Copy + PartialEq> {
+pub struct SinglyLinkedList<T> {
     len: usize,
     head: Option<NonNull<Node<T>>>,
+    tail: Option<NonNull<Node<T>>>,
     marker: PhantomData<Box<Node<T>>>,
 }
 
-impl<T: Copy + PartialEq> SinglyLinkedList<T> {
+// The list owns its nodes just like a `Box` would, so it can cross threads
+// whenever `T` can.
+unsafe impl<T: Send> Send for SinglyLinkedList<T> {}
+unsafe impl<T: Sync> Sync for SinglyLinkedList<T> {}
+
+impl<T> SinglyLinkedList<T> {
     pub fn new() -> Self {
         Self {
             len: 0,
             head: None,
+            tail: None,
             marker: PhantomData,
         }
     }
@@ -35,6 +59,9 @@ impl<T: Copy + PartialEq> SinglyLinkedList<T> {
         let mut node = Box::new(Node::new(data));
         node.next = self.head;
         let node_ptr = NonNull::new(Box::into_raw(node));
+        if self.tail.is_none() {
+            self.tail = node_ptr;
+        }
         self.head = node_ptr;
         self.len += 1;
     }
@@ -43,18 +70,12 @@ impl<T: Copy + PartialEq> SinglyLinkedList<T> {
         let node = Box::new(Node::new(data));
         let node_ptr = NonNull::new(Box::into_raw(node));
 
-        match self.head {
+        match self.tail {
             None => self.head = node_ptr,
-            Some(mut curr) => {
-                while let Some(next_ptr) = unsafe { (*curr.as_ptr()).next } {
-                    curr = next_ptr;
-                }
-                unsafe {
-                    (*curr.as_ptr()).next = node_ptr;
-                }
-            }
+            Some(tail_ptr) => unsafe { (*tail_ptr.as_ptr()).next = node_ptr },
         }
 
+        self.tail = node_ptr;
         self.len += 1
     }
 
@@ -101,11 +122,15 @@ impl<T: Copy + PartialEq> SinglyLinkedList<T> {
         self.head.map(|head_ptr| unsafe {
             let head = Box::from_raw(head_ptr.as_ptr());
             self.head = head.next;
+            if self.head.is_none() {
+                self.tail = None;
+            }
             self.len -= 1;
             head.data
         })
     }
 
+    /// Still O(n): the new tail has to be found by walking from the head.
     pub fn pop_back(&mut self) -> Option<T> {
         if self.len == 0 {
             return None;
@@ -113,6 +138,7 @@ impl<T: Copy + PartialEq> SinglyLinkedList<T> {
 
         if self.len == 1 {
             let head = self.head.take();
+            self.tail = None;
             self.len = 0;
             return head.map(|head_ptr| unsafe {
                 let head = Box::from_raw(head_ptr.as_ptr());
@@ -129,6 +155,7 @@ impl<T: Copy + PartialEq> SinglyLinkedList<T> {
                     unsafe {
                         (*prev_ptr.as_ptr()).next = None;
                     }
+                    self.tail = prev;
                     self.len -= 1;
                     return Some(unsafe {
                         let last_node = Box::from_raw(curr_ptr.as_ptr());
@@ -191,6 +218,88 @@ impl<T: Copy + PartialEq> SinglyLinkedList<T> {
         self.len == 0
     }
 
+    pub fn front(&self) -> Option<&T> {
+        self.head
+            .map(|head_ptr| unsafe { &(*head_ptr.as_ptr()).data })
+    }
+
+    pub fn front_mut(&mut self) -> Option<&mut T> {
+        self.head
+            .map(|head_ptr| unsafe { &mut (*head_ptr.as_ptr()).data })
+    }
+
+    pub fn back(&self) -> Option<&T> {
+        self.tail
+            .map(|tail_ptr| unsafe { &(*tail_ptr.as_ptr()).data })
+    }
+
+    pub fn back_mut(&mut self) -> Option<&mut T> {
+        self.tail
+            .map(|tail_ptr| unsafe { &mut (*tail_ptr.as_ptr()).data })
+    }
+
+    /// Moves all of `other` onto the end of this list in O(1), leaving
+    /// `other` empty.
+    pub fn append(&mut self, other: &mut SinglyLinkedList<T>) {
+        let Some(other_head) = other.head.take() else {
+            return;
+        };
+
+        match self.tail {
+            None => self.head = Some(other_head),
+            Some(tail_ptr) => unsafe { (*tail_ptr.as_ptr()).next = Some(other_head) },
+        }
+
+        self.tail = other.tail.take();
+        self.len += other.len;
+        other.len = 0;
+    }
+
+    /// Splits the list in two at `at`, returning everything from `at` on.
+    pub fn split_off(&mut self, at: usize) -> SinglyLinkedList<T> {
+        if self.len < at {
+            panic!("Index out of bounds")
+        }
+
+        if at == 0 {
+            return mem::take(self);
+        }
+
+        let mut last = self.head;
+        for _ in 1..at {
+            last = last.and_then(|node_ptr| unsafe { (*node_ptr.as_ptr()).next });
+        }
+
+        let mut second = SinglyLinkedList::new();
+        if let Some(last_ptr) = last {
+            second.head = unsafe { (*last_ptr.as_ptr()).next.take() };
+            if second.head.is_some() {
+                second.tail = self.tail;
+                second.len = self.len - at;
+                self.tail = last;
+                self.len = at;
+            }
+        }
+
+        second
+    }
+
+    /// Reverses the list in place by relinking the nodes.
+    pub fn reverse(&mut self) {
+        let mut prev: Option<NonNull<Node<T>>> = None;
+        let mut curr = self.head;
+        while let Some(curr_ptr) = curr {
+            unsafe {
+                curr = (*curr_ptr.as_ptr()).next;
+                (*curr_ptr.as_ptr()).next = prev;
+            }
+            prev = Some(curr_ptr);
+        }
+
+        self.tail = self.head;
+        self.head = prev;
+    }
+
     pub fn get(&self, index: usize) -> Option<&T> {
         if self.len <= index {
             panic!("Index out of bounds")
@@ -210,41 +319,263 @@ impl<T: Copy + PartialEq> SinglyLinkedList<T> {
         None
     }
 
-    pub fn contains(&self, data: T) -> bool {
-        let mut curr = self.head;
-        while let Some(curr_ptr) = curr {
-            if unsafe { (*curr_ptr.as_ptr()).data == data } {
-                return true;
-            }
+    pub fn get_mut(&mut self, index: usize) -> Option<&mut T> {
+        if self.len <= index {
+            panic!("Index out of bounds")
+        }
 
-            curr = unsafe { (*curr_ptr.as_ptr()).next };
+        self.iter_mut().nth(index)
+    }
+
+    pub fn iter(&self) -> Iter<'_, T> {
+        Iter {
+            next: self.head,
+            len: self.len,
+            marker: PhantomData,
         }
+    }
+
+    pub fn iter_mut(&mut self) -> IterMut<'_, T> {
+        IterMut {
+            next: self.head,
+            len: self.len,
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<T> SinglyLinkedList<T> {
+    /// Stable merge sort that relinks the nodes in place, without allocating
+    /// or moving any elements.
+    pub fn sort_by<F>(&mut self, mut compare: F)
+    where
+        F: FnMut(&T, &T) -> Ordering,
+    {
+        unsafe { merge_sort::sort_by(&mut self.head, &mut self.tail, self.len, &mut compare) }
+    }
 
-        false
+    pub fn sort_by_key<K, F>(&mut self, mut f: F)
+    where
+        K: Ord,
+        F: FnMut(&T) -> K,
+    {
+        self.sort_by(|a, b| f(a).cmp(&f(b)))
     }
+}
+
+impl<T: Ord> SinglyLinkedList<T> {
+    pub fn sort(&mut self) {
+        self.sort_by(T::cmp)
+    }
+
+    /// Merges the sorted `other` into this sorted list, leaving `other` empty.
+    /// On ties, elements already in this list come first.
+    pub fn merge(&mut self, other: &mut SinglyLinkedList<T>) {
+        let right = other.head.take();
+        other.tail = None;
+        self.len += mem::take(&mut other.len);
 
+        unsafe { merge_sort::merge(&mut self.head, &mut self.tail, right, &mut T::cmp) }
+    }
+}
+
+impl<T: PartialEq> SinglyLinkedList<T> {
+    pub fn contains(&self, data: &T) -> bool {
+        self.iter().any(|elt| elt == data)
+    }
+}
+
+impl<T: Clone> SinglyLinkedList<T> {
     pub fn collect(&self) -> Vec<T> {
-        let mut result = Vec::with_capacity(self.len);
-        let mut curr = self.head;
-        while let Some(node_ptr) = curr {
-            unsafe {
-                let node = &*node_ptr.as_ptr();
-                result.push(node.data.clone());
-                curr = node.next;
-            }
-        }
+        self.iter().cloned().collect()
+    }
+}
 
-        result
+impl<T> Default for SinglyLinkedList<T> {
+    fn default() -> Self {
+        Self::new()
     }
 }
 
-impl<T: Copy + PartialEq> Drop for SinglyLinkedList<T> {
+impl<T> Drop for SinglyLinkedList<T> {
     fn drop(&mut self) {
         while self.pop_front().is_some() {}
     }
 }
 
-impl<T: Copy + PartialEq + Display> Display for SinglyLinkedList<T> {
+impl<T> FromIterator<T> for SinglyLinkedList<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut list = SinglyLinkedList::new();
+        list.extend(iter);
+        list
+    }
+}
+
+impl<T> Extend<T> for SinglyLinkedList<T> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for data in iter {
+            self.push_back(data);
+        }
+    }
+}
+
+impl<T: Clone> Clone for SinglyLinkedList<T> {
+    fn clone(&self) -> Self {
+        self.iter().cloned().collect()
+    }
+}
+
+impl<T: Debug> Debug for SinglyLinkedList<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+impl<T: PartialEq> PartialEq for SinglyLinkedList<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.len == other.len && self.iter().eq(other.iter())
+    }
+}
+
+impl<T: Eq> Eq for SinglyLinkedList<T> {}
+
+impl<T: Hash> Hash for SinglyLinkedList<T> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.len.hash(state);
+        for elem in self.iter() {
+            elem.hash(state);
+        }
+    }
+}
+
+impl<T: PartialOrd> PartialOrd for SinglyLinkedList<T> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        self.iter().partial_cmp(other.iter())
+    }
+}
+
+impl<T: Ord> Ord for SinglyLinkedList<T> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.iter().cmp(other.iter())
+    }
+}
+
+impl<T> Index<usize> for SinglyLinkedList<T> {
+    type Output = T;
+
+    fn index(&self, index: usize) -> &T {
+        self.get(index).unwrap()
+    }
+}
+
+impl<T> IndexMut<usize> for SinglyLinkedList<T> {
+    fn index_mut(&mut self, index: usize) -> &mut T {
+        self.get_mut(index).unwrap()
+    }
+}
+
+impl<T> IntoIterator for SinglyLinkedList<T> {
+    type Item = T;
+    type IntoIter = IntoIter<T>;
+
+    fn into_iter(self) -> IntoIter<T> {
+        IntoIter { list: self }
+    }
+}
+
+impl<'a, T> IntoIterator for &'a SinglyLinkedList<T> {
+    type Item = &'a T;
+    type IntoIter = Iter<'a, T>;
+
+    fn into_iter(self) -> Iter<'a, T> {
+        self.iter()
+    }
+}
+
+impl<'a, T> IntoIterator for &'a mut SinglyLinkedList<T> {
+    type Item = &'a mut T;
+    type IntoIter = IterMut<'a, T>;
+
+    fn into_iter(self) -> IterMut<'a, T> {
+        self.iter_mut()
+    }
+}
+
+pub struct Iter<'a, T> {
+    next: Option<NonNull<Node<T>>>,
+    len: usize,
+    marker: PhantomData<&'a Node<T>>,
+}
+
+unsafe impl<T: Sync> Send for Iter<'_, T> {}
+unsafe impl<T: Sync> Sync for Iter<'_, T> {}
+
+impl<'a, T> Iterator for Iter<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<&'a T> {
+        self.next.map(|node_ptr| unsafe {
+            let node = &*node_ptr.as_ptr();
+            self.next = node.next;
+            self.len -= 1;
+            &node.data
+        })
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.len, Some(self.len))
+    }
+}
+
+impl<T> ExactSizeIterator for Iter<'_, T> {}
+
+pub struct IterMut<'a, T> {
+    next: Option<NonNull<Node<T>>>,
+    len: usize,
+    marker: PhantomData<&'a mut Node<T>>,
+}
+
+unsafe impl<T: Send> Send for IterMut<'_, T> {}
+unsafe impl<T: Sync> Sync for IterMut<'_, T> {}
+
+impl<'a, T> Iterator for IterMut<'a, T> {
+    type Item = &'a mut T;
+
+    fn next(&mut self) -> Option<&'a mut T> {
+        self.next.map(|node_ptr| unsafe {
+            let node = &mut *node_ptr.as_ptr();
+            self.next = node.next;
+            self.len -= 1;
+            &mut node.data
+        })
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.len, Some(self.len))
+    }
+}
+
+impl<T> ExactSizeIterator for IterMut<'_, T> {}
+
+pub struct IntoIter<T> {
+    list: SinglyLinkedList<T>,
+}
+
+impl<T> Iterator for IntoIter<T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        self.list.pop_front()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.list.len, Some(self.list.len))
+    }
+}
+
+impl<T> ExactSizeIterator for IntoIter<T> {}
+
+impl<T: Display> Display for SinglyLinkedList<T> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self.head {
             Some(node) => write!(f, "{}", unsafe { node.as_ref() }),
@@ -253,7 +584,7 @@ impl<T: Copy + PartialEq + Display> Display for SinglyLinkedList<T> {
     }
 }
 
-impl<T: Copy + PartialEq + Display> Display for Node<T> {
+impl<T: Display> Display for Node<T> {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
         match self.next {
             Some(node) => write!(f, "{} {}", self.data, unsafe { node.as_ref() }),
@@ -262,9 +593,58 @@ impl<T: Copy + PartialEq + Display> Display for Node<T> {
     }
 }
 
+/// Compile-time checks on variance and thread safety.
+///
+/// `IterMut` must not be covariant, or a `&'static str` slot could be
+/// overwritten through it with a shorter-lived reference:
+///
+/// ```compile_fail
+/// use dust::lists::singly_linked_list::IterMut;
+///
+/// fn shorten<'a>(iter: IterMut<'a, &'static str>) -> IterMut<'a, &'a str> {
+///     iter
+/// }
+/// ```
+///
+/// A list of `Rc`s must not be sent to another thread:
+///
+/// ```compile_fail
+/// use dust::lists::SinglyLinkedList;
+/// use std::rc::Rc;
+///
+/// let list: SinglyLinkedList<Rc<i32>> = SinglyLinkedList::new();
+/// std::thread::spawn(move || drop(list));
+/// ```
+///
+/// Nor may an iterator over `Cell`s, since it hands out shared references:
+///
+/// ```compile_fail
+/// use dust::lists::SinglyLinkedList;
+/// use std::cell::Cell;
+///
+/// let list: SinglyLinkedList<Cell<i32>> = SinglyLinkedList::new();
+/// let iter = list.iter();
+/// std::thread::scope(|s| {
+///     s.spawn(move || drop(iter));
+/// });
+/// ```
+#[allow(dead_code)]
+fn assert_covariance() {
+    fn list<'a>(x: SinglyLinkedList<&'static str>) -> SinglyLinkedList<&'a str> {
+        x
+    }
+    fn iter<'i, 'a>(x: Iter<'i, &'static str>) -> Iter<'i, &'a str> {
+        x
+    }
+    fn into_iter<'a>(x: IntoIter<&'static str>) -> IntoIter<&'a str> {
+        x
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::SinglyLinkedList;
+    use crate::test_util::hash_of;
 
     #[test]
     fn push_front_works() {
@@ -449,10 +829,263 @@ mod tests {
         list.push_back(2);
         list.push_back(3);
 
-        assert!(list.contains(2));
-        assert!(!list.contains(5));
+        assert!(list.contains(&2));
+        assert!(!list.contains(&5));
 
         let vec = list.collect();
         assert_eq!(vec, vec![1, 2, 3])
     }
+
+    #[test]
+    fn owned_values_work() {
+        let mut list = SinglyLinkedList::new();
+        list.push_back(String::from("b"));
+        list.push_front(String::from("a"));
+        list.push_back(String::from("c"));
+
+        assert!(list.contains(&String::from("b")));
+        assert_eq!(list.remove(1), Some(String::from("b")));
+        assert_eq!(list.pop_back(), Some(String::from("c")));
+        assert_eq!(list.collect(), vec![String::from("a")]);
+    }
+
+    #[test]
+    fn iter_works() {
+        let mut list = SinglyLinkedList::new();
+        list.push_back(1);
+        list.push_back(2);
+        list.push_back(3);
+
+        for elt in list.iter_mut() {
+            *elt *= 10;
+        }
+
+        let mut iter = list.iter();
+        assert_eq!(iter.len(), 3);
+        assert_eq!(iter.next(), Some(&10));
+        assert_eq!(iter.next(), Some(&20));
+        assert_eq!(iter.next(), Some(&30));
+        assert_eq!(iter.next(), None);
+    }
+
+    #[test]
+    fn into_iter_works() {
+        let list: SinglyLinkedList<String> =
+            ["a", "b", "c"].iter().map(|s| s.to_string()).collect();
+
+        let mut iter = list.into_iter();
+        assert_eq!(iter.len(), 3);
+        assert_eq!(iter.next(), Some(String::from("a")));
+        assert_eq!(iter.collect::<Vec<_>>(), vec!["b", "c"]);
+    }
+
+    #[test]
+    fn extend_and_for_loops_work() {
+        let mut list: SinglyLinkedList<i32> = (1..=2).collect();
+        list.extend(3..=4);
+        assert_eq!(list.len(), 4);
+
+        for elt in &mut list {
+            *elt += 1;
+        }
+
+        let mut seen = Vec::new();
+        for elt in &list {
+            seen.push(*elt);
+        }
+        assert_eq!(seen, vec![2, 3, 4, 5]);
+    }
+
+    #[test]
+    fn tail_tracks_every_operation() {
+        let mut list = SinglyLinkedList::new();
+        assert_eq!(list.back(), None);
+
+        list.push_front(2);
+        assert_eq!(list.back(), Some(&2));
+        list.push_back(3);
+        list.insert(2, 4);
+        assert_eq!(list.back(), Some(&4));
+
+        assert_eq!(list.pop_back(), Some(4));
+        assert_eq!(list.back(), Some(&3));
+        assert_eq!(list.remove(1), Some(3));
+        assert_eq!(list.back(), Some(&2));
+
+        assert_eq!(list.pop_front(), Some(2));
+        assert_eq!(list.back(), None);
+        list.push_back(5);
+        assert_eq!(list.front(), Some(&5));
+        assert_eq!(list.back(), Some(&5));
+    }
+
+    #[test]
+    fn front_and_back_mut_work() {
+        let mut list: SinglyLinkedList<i32> = (1..=3).collect();
+        *list.front_mut().unwrap() = 10;
+        *list.back_mut().unwrap() = 30;
+
+        assert_eq!(list.collect(), vec![10, 2, 30]);
+    }
+
+    #[test]
+    fn append_works() {
+        let mut list: SinglyLinkedList<i32> = (1..=2).collect();
+        let mut other: SinglyLinkedList<i32> = (3..=4).collect();
+
+        list.append(&mut other);
+        assert_eq!(list.collect(), vec![1, 2, 3, 4]);
+        assert_eq!(list.len(), 4);
+        assert!(other.is_empty());
+        assert_eq!(other.back(), None);
+
+        list.push_back(5);
+        assert_eq!(list.back(), Some(&5));
+
+        let mut empty = SinglyLinkedList::new();
+        empty.append(&mut list);
+        assert_eq!(empty.collect(), vec![1, 2, 3, 4, 5]);
+        assert_eq!(empty.back(), Some(&5));
+    }
+
+    #[test]
+    fn split_off_works() {
+        let mut list: SinglyLinkedList<i32> = (0..5).collect();
+
+        let mut tail = list.split_off(3);
+        assert_eq!(list.collect(), vec![0, 1, 2]);
+        assert_eq!(tail.collect(), vec![3, 4]);
+        assert_eq!(list.back(), Some(&2));
+        assert_eq!(tail.back(), Some(&4));
+        assert_eq!((list.len(), tail.len()), (3, 2));
+
+        assert!(list.split_off(3).is_empty());
+        let all = tail.split_off(0);
+        assert!(tail.is_empty());
+        assert_eq!(tail.back(), None);
+        assert_eq!(all.collect(), vec![3, 4]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn split_off_panics() {
+        let mut list: SinglyLinkedList<i32> = (0..2).collect();
+        list.split_off(3);
+    }
+
+    #[test]
+    fn reverse_works() {
+        let mut list: SinglyLinkedList<i32> = (1..=4).collect();
+        list.reverse();
+        assert_eq!(list.collect(), vec![4, 3, 2, 1]);
+        assert_eq!(list.back(), Some(&1));
+
+        list.push_back(0);
+        assert_eq!(list.collect(), vec![4, 3, 2, 1, 0]);
+
+        let mut empty: SinglyLinkedList<i32> = SinglyLinkedList::new();
+        empty.reverse();
+        assert!(empty.is_empty());
+    }
+
+    #[test]
+    fn sort_works() {
+        let mut list: SinglyLinkedList<i32> = vec![5, 3, 9, 1, 1, 8, 2, 7, 0].into_iter().collect();
+        list.sort();
+        assert_eq!(list.collect(), vec![0, 1, 1, 2, 3, 5, 7, 8, 9]);
+        assert_eq!(list.back(), Some(&9));
+        assert_eq!(list.len(), 9);
+
+        list.sort_by(|a, b| b.cmp(a));
+        assert_eq!(list.collect(), vec![9, 8, 7, 5, 3, 2, 1, 1, 0]);
+
+        let mut empty: SinglyLinkedList<i32> = SinglyLinkedList::new();
+        empty.sort();
+        assert!(empty.is_empty());
+    }
+
+    #[test]
+    fn sort_by_key_is_stable() {
+        let mut list: SinglyLinkedList<(i32, char)> =
+            vec![(2, 'a'), (1, 'b'), (2, 'c'), (1, 'd'), (0, 'e')]
+                .into_iter()
+                .collect();
+        list.sort_by_key(|&(key, _)| key);
+
+        assert_eq!(
+            list.collect(),
+            vec![(0, 'e'), (1, 'b'), (1, 'd'), (2, 'a'), (2, 'c')]
+        );
+    }
+
+    #[test]
+    fn sort_keeps_every_node_when_compare_panics() {
+        let mut list: SinglyLinkedList<String> = (0..20).map(|i| (20 - i).to_string()).collect();
+        let mut calls = 0;
+
+        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            list.sort_by(|a, b| {
+                calls += 1;
+                if calls == 25 {
+                    panic!("compare panicked");
+                }
+                a.cmp(b)
+            })
+        }));
+
+        assert!(result.is_err());
+        assert_eq!(list.iter().count(), 20);
+        list.push_back(String::from("last"));
+        assert_eq!(list.back().map(|s| s.as_str()), Some("last"));
+    }
+
+    #[test]
+    fn merge_works() {
+        let mut list: SinglyLinkedList<i32> = vec![1, 4, 6, 9].into_iter().collect();
+        let mut other: SinglyLinkedList<i32> = vec![2, 4, 10].into_iter().collect();
+
+        list.merge(&mut other);
+        assert_eq!(list.collect(), vec![1, 2, 4, 4, 6, 9, 10]);
+        assert_eq!(list.back(), Some(&10));
+        assert_eq!(list.len(), 7);
+        assert!(other.is_empty());
+    }
+
+    #[test]
+    fn hashes_and_orders_like_std_linked_list() {
+        let list: SinglyLinkedList<i32> = (1..=3).collect();
+        let std_list: std::collections::LinkedList<i32> = (1..=3).collect();
+        assert_eq!(hash_of(&list), hash_of(&std_list));
+        assert_eq!(format!("{:?}", list), format!("{:?}", std_list));
+
+        // A prefix sorts first but never hashes the same.
+        let prefix: SinglyLinkedList<i32> = (1..=2).collect();
+        assert!(prefix < list);
+        assert_ne!(hash_of(&prefix), hash_of(&list));
+
+        // Writing through the last index is visible from the tail.
+        let mut copy = list.clone();
+        copy[2] += 1;
+        assert_eq!(copy.back(), Some(&4));
+        assert!(list < copy);
+    }
+
+    #[test]
+    fn send_and_sync_work() {
+        let mut list: SinglyLinkedList<String> = ["a", "b"].iter().map(|s| s.to_string()).collect();
+        list = std::thread::spawn(move || {
+            list.push_back("c".to_string());
+            list
+        })
+        .join()
+        .unwrap();
+
+        let list = &list;
+        std::thread::scope(|s| {
+            let lens = s.spawn(move || list.iter().map(String::len).sum::<usize>());
+            let joined = s.spawn(move || list.iter().cloned().collect::<String>());
+            assert_eq!(lens.join().unwrap(), 3);
+            assert_eq!(joined.join().unwrap(), "abc");
+        });
+    }
 }
@@ -0,0 +1,192 @@
+use std::marker::PhantomData;
+use std::ops::{Bound, RangeBounds};
+use std::ptr;
+use std::slice;
+
+// The element-shuffling parts of `Vec` and `SmallVec`, which only differ in
+// where their buffer lives. Each works on the `*len` initialized elements at
+// `ptr`, and keeps `*len` covering only live elements so a panic leaks
+// elements rather than double dropping them.
+
+pub(crate) unsafe fn retain<T, F>(ptr: *mut T, len: &mut usize, mut f: F)
+where
+    F: FnMut(&T) -> bool,
+{
+    let original_len = *len;
+    let mut deleted = 0;
+
+    *len = 0;
+    for i in 0..original_len {
+        let curr = ptr.add(i);
+        if !f(&*curr) {
+            ptr::drop_in_place(curr);
+            deleted += 1;
+        } else if deleted > 0 {
+            ptr::copy_nonoverlapping(curr, ptr.add(i - deleted), 1);
+        }
+    }
+    *len = original_len - deleted;
+}
+
+pub(crate) unsafe fn dedup_by<T, F>(ptr: *mut T, len: &mut usize, mut same_bucket: F)
+where
+    F: FnMut(&mut T, &mut T) -> bool,
+{
+    if *len <= 1 {
+        return;
+    }
+
+    let original_len = *len;
+    let mut kept = 1;
+
+    *len = 0;
+    for i in 1..original_len {
+        let curr = ptr.add(i);
+        let prev = ptr.add(kept - 1);
+        if same_bucket(&mut *curr, &mut *prev) {
+            ptr::drop_in_place(curr);
+        } else {
+            if kept != i {
+                ptr::copy_nonoverlapping(curr, ptr.add(kept), 1);
+            }
+            kept += 1;
+        }
+    }
+    *len = kept;
+}
+
+// The elements `front..back` of a buffer that have yet to be moved out.
+pub(crate) struct Remaining {
+    front: usize,
+    back: usize,
+}
+
+impl Remaining {
+    pub(crate) fn new(front: usize, back: usize) -> Self {
+        Remaining { front, back }
+    }
+
+    pub(crate) fn len(&self) -> usize {
+        self.back - self.front
+    }
+
+    pub(crate) unsafe fn next<T>(&mut self, ptr: *const T) -> Option<T> {
+        if self.front == self.back {
+            return None;
+        }
+
+        let elem = ptr::read(ptr.add(self.front));
+        self.front += 1;
+        Some(elem)
+    }
+
+    pub(crate) unsafe fn next_back<T>(&mut self, ptr: *const T) -> Option<T> {
+        if self.front == self.back {
+            return None;
+        }
+
+        self.back -= 1;
+        Some(ptr::read(ptr.add(self.back)))
+    }
+
+    pub(crate) unsafe fn as_slice<'a, T>(&self, ptr: *const T) -> &'a [T] {
+        slice::from_raw_parts(ptr.add(self.front), self.len())
+    }
+
+    // drop_in_place keeps dropping the rest of the slice if one element panics.
+    pub(crate) unsafe fn drop_all<T>(&mut self, ptr: *mut T) {
+        let remaining = ptr::slice_from_raw_parts_mut(ptr.add(self.front), self.len());
+        self.front = self.back;
+        ptr::drop_in_place(remaining);
+    }
+}
+
+pub(crate) struct RawDrain<'a, T> {
+    ptr: *mut T,
+    len: &'a mut usize,
+    remaining: Remaining,
+    tail_start: usize,
+    tail_len: usize,
+    marker: PhantomData<&'a mut [T]>,
+}
+
+impl<'a, T> RawDrain<'a, T> {
+    // Takes `range` out of the buffer at `ptr`, which must stay put until
+    // the drain is dropped.
+    pub(crate) unsafe fn new<R>(ptr: *mut T, len: &'a mut usize, range: R) -> Self
+    where
+        R: RangeBounds<usize>,
+    {
+        let start = match range.start_bound() {
+            Bound::Included(&start) => start,
+            Bound::Excluded(&start) => start.checked_add(1).expect("range out of bounds"),
+            Bound::Unbounded => 0,
+        };
+        let end = match range.end_bound() {
+            Bound::Included(&end) => end.checked_add(1).expect("range out of bounds"),
+            Bound::Excluded(&end) => end,
+            Bound::Unbounded => *len,
+        };
+        assert!(start <= end && end <= *len, "range out of bounds");
+
+        let tail_len = *len - end;
+        // The drained elements and the tail are invisible to the owner until
+        // the drain is dropped.
+        *len = start;
+
+        RawDrain {
+            ptr,
+            len,
+            remaining: Remaining::new(start, end),
+            tail_start: end,
+            tail_len,
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<T> Iterator for RawDrain<'_, T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        unsafe { self.remaining.next(self.ptr) }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.remaining.len();
+        (len, Some(len))
+    }
+}
+
+impl<T> DoubleEndedIterator for RawDrain<'_, T> {
+    fn next_back(&mut self) -> Option<T> {
+        unsafe { self.remaining.next_back(self.ptr) }
+    }
+}
+
+impl<T> Drop for RawDrain<'_, T> {
+    fn drop(&mut self) {
+        // Slides the tail back into place even if dropping an element panics.
+        struct DropGuard<'r, 'a, T>(&'r mut RawDrain<'a, T>);
+
+        impl<T> Drop for DropGuard<'_, '_, T> {
+            fn drop(&mut self) {
+                let drain = &mut *self.0;
+                if drain.tail_len > 0 {
+                    unsafe {
+                        ptr::copy(
+                            drain.ptr.add(drain.tail_start),
+                            drain.ptr.add(*drain.len),
+                            drain.tail_len,
+                        );
+                    }
+                }
+                *drain.len += drain.tail_len;
+            }
+        }
+
+        let guard = DropGuard(self);
+        unsafe { guard.0.remaining.drop_all(guard.0.ptr) };
+        drop(guard);
+    }
+}
@@ -0,0 +1,577 @@
+use super::raw_vec::{handle_reserve, RawVec};
+use super::slice_ops::{self, RawDrain, Remaining};
+use crate::allocator::{Global, TryReserveError};
+use std::marker::PhantomData;
+use std::mem::{self, MaybeUninit};
+use std::ops::{Deref, DerefMut, RangeBounds};
+use std::ptr;
+
+enum Data<T, const N: usize> {
+    Inline([MaybeUninit<T>; N]),
+    Heap(RawVec<T>),
+}
+
+impl<T, const N: usize> Data<T, N> {
+    fn inline() -> Self {
+        Data::Inline([const { MaybeUninit::uninit() }; N])
+    }
+
+    fn as_ptr(&self) -> *const T {
+        match self {
+            Data::Inline(inline) => inline.as_ptr() as *const T,
+            Data::Heap(buffer) => buffer.ptr.as_ptr(),
+        }
+    }
+
+    fn as_mut_ptr(&mut self) -> *mut T {
+        match self {
+            Data::Inline(inline) => inline.as_mut_ptr() as *mut T,
+            Data::Heap(buffer) => buffer.ptr.as_ptr(),
+        }
+    }
+}
+
+/// A vector that keeps up to `N` elements inline and only moves them to the
+/// heap once it outgrows that.
+pub struct SmallVec<T, const N: usize> {
+    data: Data<T, N>,
+    len: usize,
+}
+
+impl<T, const N: usize> SmallVec<T, N> {
+    fn ptr(&self) -> *const T {
+        self.data.as_ptr()
+    }
+
+    fn ptr_mut(&mut self) -> *mut T {
+        self.data.as_mut_ptr()
+    }
+
+    pub fn new() -> Self {
+        SmallVec {
+            data: Data::inline(),
+            len: 0,
+        }
+    }
+
+    pub fn with_capacity(capacity: usize) -> Self {
+        let data = if capacity <= N {
+            Data::inline()
+        } else {
+            Data::Heap(RawVec::with_capacity_in(capacity, Global))
+        };
+
+        SmallVec { data, len: 0 }
+    }
+
+    pub fn capacity(&self) -> usize {
+        match &self.data {
+            Data::Inline(_) => N,
+            Data::Heap(buffer) => buffer.cap,
+        }
+    }
+
+    /// Returns true once the elements have moved to the heap.
+    pub fn spilled(&self) -> bool {
+        matches!(self.data, Data::Heap(_))
+    }
+
+    pub fn reserve(&mut self, additional: usize) {
+        handle_reserve(self.try_reserve(additional))
+    }
+
+    pub fn reserve_exact(&mut self, additional: usize) {
+        handle_reserve(self.try_reserve_exact(additional))
+    }
+
+    pub fn try_reserve(&mut self, additional: usize) -> Result<(), TryReserveError> {
+        self.try_grow(additional, false)
+    }
+
+    pub fn try_reserve_exact(&mut self, additional: usize) -> Result<(), TryReserveError> {
+        self.try_grow(additional, true)
+    }
+
+    fn try_grow(&mut self, additional: usize, exact: bool) -> Result<(), TryReserveError> {
+        let needed = self
+            .len
+            .checked_add(additional)
+            .ok_or(TryReserveError::CapacityOverflow)?;
+        if needed <= self.capacity() {
+            return Ok(());
+        }
+
+        match &mut self.data {
+            Data::Heap(buffer) if exact => buffer.try_reallocate(needed),
+            Data::Heap(buffer) => buffer.grow_amortized(self.len, additional),
+            Data::Inline(inline) => {
+                let new_cap = if exact {
+                    needed
+                } else {
+                    needed.max(N.saturating_mul(2))
+                };
+                let mut buffer = RawVec::new_in(Global);
+                if new_cap > buffer.cap {
+                    buffer.try_reallocate(new_cap)?;
+                }
+
+                unsafe {
+                    ptr::copy_nonoverlapping(
+                        inline.as_ptr() as *const T,
+                        buffer.ptr.as_ptr(),
+                        self.len,
+                    );
+                }
+                self.data = Data::Heap(buffer);
+                Ok(())
+            }
+        }
+    }
+
+    /// Moves the elements back inline if they fit, otherwise trims the heap
+    /// buffer to the length.
+    pub fn shrink_to_fit(&mut self) {
+        let Data::Heap(buffer) = &mut self.data else {
+            return;
+        };
+
+        if self.len <= N {
+            let mut inline = Data::inline();
+            unsafe {
+                ptr::copy_nonoverlapping(buffer.ptr.as_ptr(), inline.as_mut_ptr(), self.len);
+            }
+            // The old buffer is freed without touching the moved elements.
+            self.data = inline;
+        } else if mem::size_of::<T>() != 0 && buffer.cap > self.len {
+            buffer.reallocate(self.len);
+        }
+    }
+
+    pub fn push(&mut self, elem: T) {
+        if self.len == self.capacity() {
+            self.reserve(1);
+        }
+
+        unsafe {
+            ptr::write(self.ptr_mut().add(self.len), elem);
+        }
+        self.len += 1;
+    }
+
+    pub fn pop(&mut self) -> Option<T> {
+        if self.len == 0 {
+            return None;
+        }
+
+        self.len -= 1;
+        unsafe { Some(ptr::read(self.ptr().add(self.len))) }
+    }
+
+    pub fn insert(&mut self, index: usize, elem: T) {
+        assert!(index <= self.len, "index out of bounds");
+        if self.len == self.capacity() {
+            self.reserve(1);
+        }
+
+        //shift all the elements from [i .. len] to [i+1 .. len+1] using the old len.
+        unsafe {
+            let ptr = self.ptr_mut();
+            ptr::copy(ptr.add(index), ptr.add(index + 1), self.len - index);
+            ptr::write(ptr.add(index), elem);
+            self.len += 1;
+        }
+    }
+
+    pub fn remove(&mut self, index: usize) -> T {
+        assert!(index < self.len, "index out of bounds");
+
+        //shift all the elements from [i+1 .. len + 1] to [i .. len] using the new len.
+        unsafe {
+            self.len -= 1;
+            let ptr = self.ptr_mut();
+            let result = ptr::read(ptr.add(index));
+            ptr::copy(ptr.add(index + 1), ptr.add(index), self.len - index);
+            result
+        }
+    }
+
+    pub fn swap_remove(&mut self, index: usize) -> T {
+        assert!(index < self.len, "index out of bounds");
+
+        //move the last element into the hole left by the removed one.
+        unsafe {
+            self.len -= 1;
+            let ptr = self.ptr_mut();
+            let result = ptr::read(ptr.add(index));
+            ptr::copy(ptr.add(self.len), ptr.add(index), 1);
+            result
+        }
+    }
+
+    pub fn truncate(&mut self, len: usize) {
+        if len >= self.len {
+            return;
+        }
+
+        // Shorten first so a panicking destructor cannot cause a double drop.
+        let tail =
+            ptr::slice_from_raw_parts_mut(unsafe { self.ptr_mut().add(len) }, self.len - len);
+        self.len = len;
+        unsafe { ptr::drop_in_place(tail) };
+    }
+
+    pub fn clear(&mut self) {
+        self.truncate(0)
+    }
+
+    pub fn retain<F>(&mut self, f: F)
+    where
+        F: FnMut(&T) -> bool,
+    {
+        let ptr = self.ptr_mut();
+        unsafe { slice_ops::retain(ptr, &mut self.len, f) }
+    }
+
+    pub fn dedup_by<F>(&mut self, same_bucket: F)
+    where
+        F: FnMut(&mut T, &mut T) -> bool,
+    {
+        let ptr = self.ptr_mut();
+        unsafe { slice_ops::dedup_by(ptr, &mut self.len, same_bucket) }
+    }
+
+    pub fn split_off(&mut self, at: usize) -> SmallVec<T, N> {
+        assert!(at <= self.len, "index out of bounds");
+
+        let other_len = self.len - at;
+        let mut other = SmallVec::with_capacity(other_len);
+        unsafe {
+            ptr::copy_nonoverlapping(self.ptr().add(at), other.ptr_mut(), other_len);
+        }
+        self.len = at;
+        other.len = other_len;
+        other
+    }
+
+    pub fn append(&mut self, other: &mut SmallVec<T, N>) {
+        self.reserve(other.len);
+        unsafe {
+            ptr::copy_nonoverlapping(other.ptr(), self.ptr_mut().add(self.len), other.len);
+        }
+        self.len += other.len;
+        other.len = 0;
+    }
+
+    pub fn drain<R>(&mut self, range: R) -> Drain<'_, T, N>
+    where
+        R: RangeBounds<usize>,
+    {
+        let ptr = self.ptr_mut();
+        Drain {
+            inner: unsafe { RawDrain::new(ptr, &mut self.len, range) },
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<T: PartialEq, const N: usize> SmallVec<T, N> {
+    pub fn dedup(&mut self) {
+        self.dedup_by(|a, b| a == b)
+    }
+}
+
+impl<T, const N: usize> Extend<T> for SmallVec<T, N> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        let iter = iter.into_iter();
+        self.reserve(iter.size_hint().0);
+        for elem in iter {
+            self.push(elem);
+        }
+    }
+}
+
+impl<'a, T: Copy + 'a, const N: usize> Extend<&'a T> for SmallVec<T, N> {
+    fn extend<I: IntoIterator<Item = &'a T>>(&mut self, iter: I) {
+        self.extend(iter.into_iter().copied())
+    }
+}
+
+pub struct Drain<'a, T, const N: usize> {
+    inner: RawDrain<'a, T>,
+    marker: PhantomData<&'a mut SmallVec<T, N>>,
+}
+
+unsafe impl<T: Send, const N: usize> Send for Drain<'_, T, N> {}
+unsafe impl<T: Sync, const N: usize> Sync for Drain<'_, T, N> {}
+
+impl<T, const N: usize> Iterator for Drain<'_, T, N> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        self.inner.next()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl<T, const N: usize> DoubleEndedIterator for Drain<'_, T, N> {
+    fn next_back(&mut self) -> Option<T> {
+        self.inner.next_back()
+    }
+}
+
+impl<T, const N: usize> ExactSizeIterator for Drain<'_, T, N> {}
+
+pub struct IntoIter<T, const N: usize> {
+    data: Data<T, N>,
+    remaining: Remaining,
+}
+
+impl<T, const N: usize> IntoIter<T, N> {
+    pub fn as_slice(&self) -> &[T] {
+        unsafe { self.remaining.as_slice(self.data.as_ptr()) }
+    }
+}
+
+impl<T, const N: usize> Iterator for IntoIter<T, N> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        unsafe { self.remaining.next(self.data.as_ptr()) }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.remaining.len();
+        (len, Some(len))
+    }
+}
+
+impl<T, const N: usize> DoubleEndedIterator for IntoIter<T, N> {
+    fn next_back(&mut self) -> Option<T> {
+        unsafe { self.remaining.next_back(self.data.as_ptr()) }
+    }
+}
+
+impl<T, const N: usize> ExactSizeIterator for IntoIter<T, N> {}
+
+impl<T, const N: usize> Drop for IntoIter<T, N> {
+    fn drop(&mut self) {
+        unsafe { self.remaining.drop_all(self.data.as_mut_ptr()) };
+        // deallocation, if spilled, is handled by RawVec
+    }
+}
+
+impl<T, const N: usize> IntoIterator for SmallVec<T, N> {
+    type Item = T;
+    type IntoIter = IntoIter<T, N>;
+
+    fn into_iter(self) -> IntoIter<T, N> {
+        // Take the storage without running SmallVec's destructor.
+        let vec = mem::ManuallyDrop::new(self);
+        let data = unsafe { ptr::read(&vec.data) };
+
+        IntoIter {
+            data,
+            remaining: Remaining::new(0, vec.len),
+        }
+    }
+}
+
+impl<'a, T, const N: usize> IntoIterator for &'a SmallVec<T, N> {
+    type Item = &'a T;
+    type IntoIter = std::slice::Iter<'a, T>;
+
+    fn into_iter(self) -> std::slice::Iter<'a, T> {
+        self.iter()
+    }
+}
+
+impl<'a, T, const N: usize> IntoIterator for &'a mut SmallVec<T, N> {
+    type Item = &'a mut T;
+    type IntoIter = std::slice::IterMut<'a, T>;
+
+    fn into_iter(self) -> std::slice::IterMut<'a, T> {
+        self.iter_mut()
+    }
+}
+
+impl<T, const N: usize> Default for SmallVec<T, N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T, const N: usize> Drop for SmallVec<T, N> {
+    fn drop(&mut self) {
+        unsafe {
+            ptr::drop_in_place(ptr::slice_from_raw_parts_mut(self.ptr_mut(), self.len));
+        }
+        // deallocation, if spilled, is handled by RawVec
+    }
+}
+
+impl<T, const N: usize> Deref for SmallVec<T, N> {
+    type Target = [T];
+    fn deref(&self) -> &[T] {
+        unsafe { std::slice::from_raw_parts(self.ptr(), self.len) }
+    }
+}
+
+impl<T, const N: usize> DerefMut for SmallVec<T, N> {
+    fn deref_mut(&mut self) -> &mut [T] {
+        unsafe { std::slice::from_raw_parts_mut(self.ptr_mut(), self.len) }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::SmallVec;
+    use crate::test_util::DropCounter;
+    use std::cell::Cell;
+
+    #[test]
+    fn stays_inline_until_full() {
+        let mut list: SmallVec<i32, 4> = SmallVec::new();
+        list.extend([1, 2, 3, 4]);
+        assert!(!list.spilled());
+        assert_eq!(list.capacity(), 4);
+
+        list.push(5);
+        assert!(list.spilled());
+        assert_eq!(list.capacity(), 8);
+        assert_eq!(&list[..], &[1, 2, 3, 4, 5]);
+    }
+
+    #[test]
+    fn with_capacity_works() {
+        let list: SmallVec<i32, 4> = SmallVec::with_capacity(3);
+        assert!(!list.spilled());
+
+        let list: SmallVec<i32, 4> = SmallVec::with_capacity(10);
+        assert!(list.spilled());
+        assert_eq!(list.capacity(), 10);
+    }
+
+    #[test]
+    fn shrink_to_fit_moves_back_inline() {
+        let mut list: SmallVec<String, 2> = SmallVec::new();
+        list.extend((0..6).map(|i| i.to_string()));
+        list.truncate(4);
+        list.shrink_to_fit();
+        assert!(list.spilled());
+        assert_eq!(list.capacity(), 4);
+
+        list.truncate(2);
+        list.shrink_to_fit();
+        assert!(!list.spilled());
+        assert_eq!(&list[..], &["0", "1"]);
+    }
+
+    #[test]
+    fn insert_and_remove_work() {
+        let mut list: SmallVec<i32, 2> = SmallVec::new();
+        list.push(1);
+        list.push(3);
+        list.insert(1, 2);
+        list.insert(0, 0);
+        assert_eq!(&list[..], &[0, 1, 2, 3]);
+
+        assert_eq!(list.remove(0), 0);
+        assert_eq!(list.swap_remove(0), 1);
+        assert_eq!(&list[..], &[3, 2]);
+        assert_eq!(list.pop(), Some(2));
+        assert_eq!(list.pop(), Some(3));
+        assert_eq!(list.pop(), None);
+    }
+
+    #[test]
+    fn retain_and_dedup_work() {
+        let mut list: SmallVec<i32, 4> = SmallVec::new();
+        list.extend([1, 1, 2, 3, 3, 4, 5, 6]);
+
+        list.dedup();
+        assert_eq!(&list[..], &[1, 2, 3, 4, 5, 6]);
+
+        list.retain(|elem| elem % 2 == 0);
+        assert_eq!(&list[..], &[2, 4, 6]);
+    }
+
+    #[test]
+    fn split_off_and_append_work() {
+        let mut list: SmallVec<i32, 4> = SmallVec::new();
+        list.extend([1, 2, 3, 4, 5, 6]);
+
+        let mut tail = list.split_off(4);
+        assert!(!tail.spilled());
+        assert_eq!(&tail[..], &[5, 6]);
+
+        list.append(&mut tail);
+        assert_eq!(&list[..], &[1, 2, 3, 4, 5, 6]);
+        assert!(tail.is_empty());
+    }
+
+    #[test]
+    fn drain_works() {
+        let mut list: SmallVec<i32, 8> = SmallVec::new();
+        list.extend([1, 2, 3, 4, 5, 6]);
+
+        let drained: Vec<i32> = list.drain(1..4).collect();
+        assert_eq!(drained, vec![2, 3, 4]);
+        assert_eq!(&list[..], &[1, 5, 6]);
+    }
+
+    #[test]
+    #[should_panic(expected = "range out of bounds")]
+    fn drain_panics_on_inclusive_usize_max() {
+        let mut list: SmallVec<i32, 8> = SmallVec::new();
+        list.extend([1, 2, 3]);
+        list.drain(1..=usize::MAX);
+    }
+
+    #[test]
+    fn into_iter_works() {
+        let mut inline: SmallVec<i32, 8> = SmallVec::new();
+        inline.extend([1, 2, 3]);
+        let mut iter = inline.into_iter();
+        assert_eq!(iter.next_back(), Some(3));
+        assert_eq!(iter.as_slice(), &[1, 2]);
+
+        let mut spilled: SmallVec<i32, 2> = SmallVec::new();
+        spilled.extend([1, 2, 3]);
+        assert_eq!(spilled.into_iter().collect::<Vec<_>>(), vec![1, 2, 3]);
+    }
+
+    #[test]
+    fn drops_each_element_once() {
+        let drops = Cell::new(0);
+        let mut list: SmallVec<DropCounter, 2> = SmallVec::new();
+        list.push(DropCounter::new(&drops));
+        list.push(DropCounter::new(&drops));
+        drop(list);
+        assert_eq!(drops.get(), 2);
+
+        let mut list: SmallVec<DropCounter, 2> = SmallVec::new();
+        for _ in 0..5 {
+            list.push(DropCounter::new(&drops));
+        }
+        let mut iter = list.into_iter();
+        drop(iter.next());
+        drop(iter);
+        assert_eq!(drops.get(), 7);
+    }
+
+    #[test]
+    fn zero_sized_types_work() {
+        let mut list: SmallVec<(), 2> = SmallVec::new();
+        for _ in 0..10 {
+            list.push(());
+        }
+
+        assert_eq!(list.len(), 10);
+        assert_eq!(list.drain(2..).count(), 8);
+        list.shrink_to_fit();
+        assert!(!list.spilled());
+    }
+}
@@ -1,81 +1,38 @@
-use std::alloc::{self, Layout};
+pub use super::raw_vec::GrowthStrategy;
+use super::raw_vec::{handle_reserve, RawVec};
+use super::slice_ops::{self, RawDrain, Remaining};
+use crate::allocator::{Allocator, Global, TryReserveError};
+use std::cmp::Ordering;
+use std::fmt::{self, Debug};
+use std::hash::{Hash, Hasher};
+use std::marker::PhantomData;
 use std::mem;
-use std::ops::Deref;
-use std::ops::DerefMut;
-use std::ptr::{self, NonNull};
+use std::ops::{Deref, DerefMut, Index, IndexMut, RangeBounds};
+use std::ptr;
+use std::slice::SliceIndex;
 
-struct RawVec<T> {
-    ptr: NonNull<T>,
-    cap: usize,
+pub struct Vec<T, A: Allocator = Global> {
+    buffer: RawVec<T, A>,
+    len: usize,
 }
 
-impl<T> RawVec<T> {
-    fn new() -> Self {
-        let cap = if mem::size_of::<T>() == 0 {
-            usize::MAX
-        } else {
-            0
-        };
-
-        RawVec {
-            ptr: NonNull::dangling(),
-            cap,
-        }
+impl<T> Vec<T> {
+    pub fn new() -> Self {
+        Self::new_in(Global)
     }
 
-    fn grow(&mut self) {
-        assert!(mem::size_of::<T>() != 0, "capacity overflow");
-
-        let (new_cap, new_layout) = if self.cap == 0 {
-            (1, Layout::array::<T>(1).unwrap())
-        } else {
-            let new_cap = 2 * self.cap;
-
-            let new_layout = Layout::array::<T>(new_cap).unwrap();
-            (new_cap, new_layout)
-        };
-
-        assert!(
-            new_layout.size() <= isize::MAX as usize,
-            "Allocation too large"
-        );
-
-        let new_ptr = if self.cap == 0 {
-            unsafe { alloc::alloc(new_layout) }
-        } else {
-            let old_layout = Layout::array::<T>(self.cap).unwrap();
-            let old_ptr = self.ptr.as_ptr() as *mut u8;
-            unsafe { alloc::realloc(old_ptr, old_layout, new_layout.size()) }
-        };
-
-        self.ptr = match NonNull::new(new_ptr as *mut T) {
-            Some(p) => p,
-            None => alloc::handle_alloc_error(new_layout),
-        };
-        self.cap = new_cap;
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self::with_capacity_in(capacity, Global)
     }
-}
 
-impl<T> Drop for RawVec<T> {
-    fn drop(&mut self) {
-        let elem_size = mem::size_of::<T>();
-
-        if self.cap != 0 && elem_size != 0 {
-            unsafe {
-                alloc::dealloc(
-                    self.ptr.as_ptr() as *mut u8,
-                    Layout::array::<T>(self.cap).unwrap(),
-                );
-            }
-        }
+    pub fn with_growth_strategy(strategy: GrowthStrategy) -> Self {
+        let mut vec = Self::new();
+        vec.set_growth_strategy(strategy);
+        vec
     }
 }
-pub struct Vec<T> {
-    buffer: RawVec<T>,
-    len: usize,
-}
 
-impl<T> Vec<T> {
+impl<T, A: Allocator> Vec<T, A> {
     fn ptr(&self) -> *mut T {
         self.buffer.ptr.as_ptr()
     }
@@ -84,13 +41,67 @@ impl<T> Vec<T> {
         self.buffer.cap
     }
 
-    pub fn new() -> Self {
+    pub fn new_in(alloc: A) -> Self {
         Vec {
-            buffer: RawVec::new(),
+            buffer: RawVec::new_in(alloc),
             len: 0,
         }
     }
 
+    pub fn with_capacity_in(capacity: usize, alloc: A) -> Self {
+        Vec {
+            buffer: RawVec::with_capacity_in(capacity, alloc),
+            len: 0,
+        }
+    }
+
+    pub fn allocator(&self) -> &A {
+        &self.buffer.alloc
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.cap()
+    }
+
+    pub fn growth_strategy(&self) -> GrowthStrategy {
+        self.buffer.growth
+    }
+
+    pub fn set_growth_strategy(&mut self, strategy: GrowthStrategy) {
+        self.buffer.growth = strategy;
+    }
+
+    pub fn reserve(&mut self, additional: usize) {
+        handle_reserve(self.try_reserve(additional))
+    }
+
+    pub fn reserve_exact(&mut self, additional: usize) {
+        handle_reserve(self.try_reserve_exact(additional))
+    }
+
+    /// Like `reserve`, but hands allocation failure back to the caller instead
+    /// of aborting. The vec is unchanged on error.
+    pub fn try_reserve(&mut self, additional: usize) -> Result<(), TryReserveError> {
+        self.buffer.grow_amortized(self.len, additional)
+    }
+
+    pub fn try_reserve_exact(&mut self, additional: usize) -> Result<(), TryReserveError> {
+        let needed = self
+            .len
+            .checked_add(additional)
+            .ok_or(TryReserveError::CapacityOverflow)?;
+        if needed > self.cap() {
+            self.buffer.try_reallocate(needed)?;
+        }
+        Ok(())
+    }
+
+    pub fn shrink_to_fit(&mut self) {
+        if mem::size_of::<T>() != 0 && self.cap() > self.len {
+            self.buffer.reallocate(self.len);
+        }
+    }
+
     pub fn push(&mut self, elem: T) {
         if self.len == self.cap() {
             self.buffer.grow();
@@ -144,31 +155,377 @@ impl<T> Vec<T> {
             result
         }
     }
+
+    pub fn swap_remove(&mut self, index: usize) -> T {
+        assert!(index < self.len, "index out of bounds");
+
+        //move the last element into the hole left by the removed one.
+        unsafe {
+            self.len -= 1;
+            let result = ptr::read(self.ptr().add(index));
+            ptr::copy(self.ptr().add(self.len), self.ptr().add(index), 1);
+            result
+        }
+    }
+
+    pub fn truncate(&mut self, len: usize) {
+        if len >= self.len {
+            return;
+        }
+
+        // Shorten first so a panicking destructor cannot cause a double drop.
+        let tail = ptr::slice_from_raw_parts_mut(unsafe { self.ptr().add(len) }, self.len - len);
+        self.len = len;
+        unsafe { ptr::drop_in_place(tail) };
+    }
+
+    pub fn clear(&mut self) {
+        self.truncate(0)
+    }
+
+    pub fn retain<F>(&mut self, f: F)
+    where
+        F: FnMut(&T) -> bool,
+    {
+        unsafe { slice_ops::retain(self.ptr(), &mut self.len, f) }
+    }
+
+    pub fn dedup_by<F>(&mut self, same_bucket: F)
+    where
+        F: FnMut(&mut T, &mut T) -> bool,
+    {
+        unsafe { slice_ops::dedup_by(self.ptr(), &mut self.len, same_bucket) }
+    }
+
+    pub fn split_off(&mut self, at: usize) -> Vec<T, A>
+    where
+        A: Clone,
+    {
+        assert!(at <= self.len, "index out of bounds");
+
+        let other_len = self.len - at;
+        let mut other = Vec::with_capacity_in(other_len, self.allocator().clone());
+        other.buffer.growth = self.buffer.growth;
+        unsafe {
+            ptr::copy_nonoverlapping(self.ptr().add(at), other.ptr(), other_len);
+        }
+        self.len = at;
+        other.len = other_len;
+        other
+    }
+
+    pub fn append(&mut self, other: &mut Vec<T, A>) {
+        self.reserve(other.len);
+        unsafe {
+            ptr::copy_nonoverlapping(other.ptr(), self.ptr().add(self.len), other.len);
+        }
+        self.len += other.len;
+        other.len = 0;
+    }
+
+    pub fn drain<R>(&mut self, range: R) -> Drain<'_, T, A>
+    where
+        R: RangeBounds<usize>,
+    {
+        let ptr = self.ptr();
+        Drain {
+            inner: unsafe { RawDrain::new(ptr, &mut self.len, range) },
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<T: PartialEq, A: Allocator> Vec<T, A> {
+    pub fn dedup(&mut self) {
+        self.dedup_by(|a, b| a == b)
+    }
 }
 
-impl<T> Drop for Vec<T> {
+impl<T, A: Allocator> Extend<T> for Vec<T, A> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        let iter = iter.into_iter();
+        self.reserve(iter.size_hint().0);
+        for elem in iter {
+            self.push(elem);
+        }
+    }
+}
+
+impl<'a, T: Copy + 'a, A: Allocator> Extend<&'a T> for Vec<T, A> {
+    fn extend<I: IntoIterator<Item = &'a T>>(&mut self, iter: I) {
+        self.extend(iter.into_iter().copied())
+    }
+}
+
+pub struct Drain<'a, T, A: Allocator = Global> {
+    inner: RawDrain<'a, T>,
+    marker: PhantomData<&'a mut Vec<T, A>>,
+}
+
+unsafe impl<T: Send, A: Allocator + Send> Send for Drain<'_, T, A> {}
+unsafe impl<T: Sync, A: Allocator + Sync> Sync for Drain<'_, T, A> {}
+
+impl<T, A: Allocator> Iterator for Drain<'_, T, A> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        self.inner.next()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl<T, A: Allocator> DoubleEndedIterator for Drain<'_, T, A> {
+    fn next_back(&mut self) -> Option<T> {
+        self.inner.next_back()
+    }
+}
+
+impl<T, A: Allocator> ExactSizeIterator for Drain<'_, T, A> {}
+
+pub struct IntoIter<T, A: Allocator = Global> {
+    buffer: RawVec<T, A>,
+    remaining: Remaining,
+}
+
+impl<T, A: Allocator> IntoIter<T, A> {
+    pub fn as_slice(&self) -> &[T] {
+        unsafe { self.remaining.as_slice(self.buffer.ptr.as_ptr()) }
+    }
+}
+
+impl<T, A: Allocator> Iterator for IntoIter<T, A> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        unsafe { self.remaining.next(self.buffer.ptr.as_ptr()) }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.remaining.len();
+        (len, Some(len))
+    }
+}
+
+impl<T, A: Allocator> DoubleEndedIterator for IntoIter<T, A> {
+    fn next_back(&mut self) -> Option<T> {
+        unsafe { self.remaining.next_back(self.buffer.ptr.as_ptr()) }
+    }
+}
+
+impl<T, A: Allocator> ExactSizeIterator for IntoIter<T, A> {}
+
+impl<T, A: Allocator> Drop for IntoIter<T, A> {
     fn drop(&mut self) {
-        while let Some(_) = self.pop() {}
+        unsafe { self.remaining.drop_all(self.buffer.ptr.as_ptr()) };
         // deallocation is handled by RawVec
     }
 }
 
-impl<T> Deref for Vec<T> {
+impl<T, A: Allocator> IntoIterator for Vec<T, A> {
+    type Item = T;
+    type IntoIter = IntoIter<T, A>;
+
+    fn into_iter(self) -> IntoIter<T, A> {
+        // Take the buffer without running Vec's destructor.
+        let vec = mem::ManuallyDrop::new(self);
+        let buffer = unsafe { ptr::read(&vec.buffer) };
+
+        IntoIter {
+            buffer,
+            remaining: Remaining::new(0, vec.len),
+        }
+    }
+}
+
+impl<'a, T, A: Allocator> IntoIterator for &'a Vec<T, A> {
+    type Item = &'a T;
+    type IntoIter = std::slice::Iter<'a, T>;
+
+    fn into_iter(self) -> std::slice::Iter<'a, T> {
+        self.iter()
+    }
+}
+
+impl<'a, T, A: Allocator> IntoIterator for &'a mut Vec<T, A> {
+    type Item = &'a mut T;
+    type IntoIter = std::slice::IterMut<'a, T>;
+
+    fn into_iter(self) -> std::slice::IterMut<'a, T> {
+        self.iter_mut()
+    }
+}
+
+impl<T> Default for Vec<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> FromIterator<T> for Vec<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut vec = Vec::new();
+        vec.extend(iter);
+        vec
+    }
+}
+
+impl<T: Clone, A: Allocator + Clone> Clone for Vec<T, A> {
+    fn clone(&self) -> Self {
+        let mut vec = Vec::with_capacity_in(self.len, self.allocator().clone());
+        vec.buffer.growth = self.buffer.growth;
+        vec.extend(self.iter().cloned());
+        vec
+    }
+}
+
+impl<T: Debug, A: Allocator> Debug for Vec<T, A> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        Debug::fmt(&**self, f)
+    }
+}
+
+impl<T: PartialEq, A: Allocator> PartialEq for Vec<T, A> {
+    fn eq(&self, other: &Self) -> bool {
+        **self == **other
+    }
+}
+
+impl<T: Eq, A: Allocator> Eq for Vec<T, A> {}
+
+impl<T: Hash, A: Allocator> Hash for Vec<T, A> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        (**self).hash(state)
+    }
+}
+
+impl<T: PartialOrd, A: Allocator> PartialOrd for Vec<T, A> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        (**self).partial_cmp(&**other)
+    }
+}
+
+impl<T: Ord, A: Allocator> Ord for Vec<T, A> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        (**self).cmp(&**other)
+    }
+}
+
+impl<T, I: SliceIndex<[T]>, A: Allocator> Index<I> for Vec<T, A> {
+    type Output = I::Output;
+
+    fn index(&self, index: I) -> &I::Output {
+        Index::index(&**self, index)
+    }
+}
+
+impl<T, I: SliceIndex<[T]>, A: Allocator> IndexMut<I> for Vec<T, A> {
+    fn index_mut(&mut self, index: I) -> &mut I::Output {
+        IndexMut::index_mut(&mut **self, index)
+    }
+}
+
+impl<T, A: Allocator> Drop for Vec<T, A> {
+    fn drop(&mut self) {
+        unsafe { ptr::drop_in_place(ptr::slice_from_raw_parts_mut(self.ptr(), self.len)) };
+        // deallocation is handled by RawVec
+    }
+}
+
+impl<T, A: Allocator> Deref for Vec<T, A> {
     type Target = [T];
     fn deref(&self) -> &[T] {
         unsafe { std::slice::from_raw_parts(self.ptr(), self.len) }
     }
 }
 
-impl<T> DerefMut for Vec<T> {
+impl<T, A: Allocator> DerefMut for Vec<T, A> {
     fn deref_mut(&mut self) -> &mut [T] {
         unsafe { std::slice::from_raw_parts_mut(self.ptr(), self.len) }
     }
 }
 
+/// Compile-time checks on thread safety.
+///
+/// A vec of `Rc`s must not be sent to another thread:
+///
+/// ```compile_fail
+/// use dust::lists::Vec;
+/// use std::rc::Rc;
+///
+/// let vec: Vec<Rc<i32>> = Vec::new();
+/// std::thread::spawn(move || drop(vec));
+/// ```
+///
+/// Nor may a vec of `Cell`s be shared between threads:
+///
+/// ```compile_fail
+/// use dust::lists::Vec;
+/// use std::cell::Cell;
+///
+/// let vec: Vec<Cell<i32>> = Vec::new();
+/// let vec = &vec;
+/// std::thread::scope(|s| {
+///     s.spawn(move || vec.len());
+/// });
+/// ```
+#[allow(dead_code)]
+fn assert_covariance() {
+    fn vec<'a>(x: Vec<&'static str>) -> Vec<&'a str> {
+        x
+    }
+    fn into_iter<'a>(x: IntoIter<&'static str>) -> IntoIter<&'a str> {
+        x
+    }
+}
+
 #[cfg(test)]
 mod tests {
-    use super::Vec;
+    use super::{GrowthStrategy, Vec};
+    use crate::allocator::{AllocError, Allocator, Global, TryReserveError};
+    use crate::test_util::{hash_of, DropCounter};
+    use std::alloc::Layout;
+    use std::cell::Cell;
+    use std::ops::Bound;
+    use std::panic::{self, AssertUnwindSafe};
+    use std::ptr::NonNull;
+
+    // Forwards to Global while refusing to hold more than `limit` bytes.
+    struct BudgetAllocator {
+        limit: usize,
+        in_use: Cell<usize>,
+        allocations: Cell<usize>,
+    }
+
+    impl BudgetAllocator {
+        fn new(limit: usize) -> Self {
+            BudgetAllocator {
+                limit,
+                in_use: Cell::new(0),
+                allocations: Cell::new(0),
+            }
+        }
+    }
+
+    unsafe impl Allocator for BudgetAllocator {
+        fn allocate(&self, layout: Layout) -> Result<NonNull<u8>, AllocError> {
+            if self.in_use.get() + layout.size() > self.limit {
+                return Err(AllocError);
+            }
+
+            let ptr = Global.allocate(layout)?;
+            self.in_use.set(self.in_use.get() + layout.size());
+            self.allocations.set(self.allocations.get() + 1);
+            Ok(ptr)
+        }
+
+        unsafe fn deallocate(&self, ptr: NonNull<u8>, layout: Layout) {
+            self.in_use.set(self.in_use.get() - layout.size());
+            Global.deallocate(ptr, layout)
+        }
+    }
 
     #[test]
     fn push_works() {
@@ -228,4 +585,360 @@ mod tests {
         assert_eq!(list[0], 1);
         assert_eq!(list[1], 3);
     }
+
+    #[test]
+    fn with_capacity_works() {
+        let mut list: Vec<i32> = Vec::with_capacity(10);
+        assert_eq!(list.capacity(), 10);
+
+        list.push(1);
+        list.reserve_exact(20);
+        assert_eq!(list.capacity(), 21);
+
+        list.reserve(5);
+        assert_eq!(list.capacity(), 21);
+
+        list.shrink_to_fit();
+        assert_eq!(list.capacity(), 1);
+        assert_eq!(list[0], 1);
+    }
+
+    #[test]
+    fn reserve_grows_geometrically() {
+        let mut list: Vec<i32> = Vec::with_capacity(8);
+        list.extend([1, 2, 3, 4, 5, 6, 7, 8]);
+        list.reserve(1);
+
+        assert_eq!(list.capacity(), 16);
+    }
+
+    #[test]
+    fn truncate_and_clear_work() {
+        let mut list = Vec::new();
+        list.extend((0..5).map(|i| i.to_string()));
+
+        list.truncate(2);
+        assert_eq!(&list[..], &["0", "1"]);
+
+        list.truncate(10);
+        assert_eq!(list.len(), 2);
+
+        list.clear();
+        assert!(list.is_empty());
+    }
+
+    #[test]
+    fn swap_remove_works() {
+        let mut list = Vec::new();
+        list.extend([1, 2, 3, 4]);
+
+        assert_eq!(list.swap_remove(1), 2);
+        assert_eq!(&list[..], &[1, 4, 3]);
+        assert_eq!(list.swap_remove(2), 3);
+        assert_eq!(&list[..], &[1, 4]);
+    }
+
+    #[test]
+    fn retain_works() {
+        let mut list = Vec::new();
+        list.extend((0..10).map(Box::new));
+
+        list.retain(|elem| **elem % 3 == 0);
+
+        assert_eq!(
+            list.iter().map(|elem| **elem).collect::<std::vec::Vec<_>>(),
+            vec![0, 3, 6, 9]
+        );
+    }
+
+    #[test]
+    fn dedup_works() {
+        let mut list = Vec::new();
+        list.extend([1, 1, 2, 3, 3, 3, 1, 4, 4]);
+
+        list.dedup();
+
+        assert_eq!(&list[..], &[1, 2, 3, 1, 4]);
+    }
+
+    #[test]
+    fn split_off_and_append_work() {
+        let mut list = Vec::new();
+        list.extend([1, 2, 3, 4, 5]);
+
+        let mut tail = list.split_off(2);
+        assert_eq!(&list[..], &[1, 2]);
+        assert_eq!(&tail[..], &[3, 4, 5]);
+
+        list.append(&mut tail);
+        assert_eq!(&list[..], &[1, 2, 3, 4, 5]);
+        assert!(tail.is_empty());
+    }
+
+    #[test]
+    fn drain_works() {
+        let mut list = Vec::new();
+        list.extend([1, 2, 3, 4, 5, 6]);
+
+        let drained: std::vec::Vec<i32> = list.drain(1..4).collect();
+        assert_eq!(drained, vec![2, 3, 4]);
+        assert_eq!(&list[..], &[1, 5, 6]);
+
+        let mut drain = list.drain(..);
+        assert_eq!(drain.next_back(), Some(6));
+        drop(drain);
+        assert!(list.is_empty());
+    }
+
+    #[test]
+    fn zero_sized_types_work() {
+        let mut list = Vec::new();
+        for _ in 0..10 {
+            list.push(());
+        }
+        list.truncate(4);
+        list.shrink_to_fit();
+
+        assert_eq!(list.len(), 4);
+        assert_eq!(list.capacity(), usize::MAX);
+        assert_eq!(list.drain(1..).count(), 3);
+    }
+
+    #[test]
+    fn into_iter_works() {
+        let mut list = Vec::new();
+        list.extend([1, 2, 3, 4]);
+
+        let mut iter = list.into_iter();
+        assert_eq!(iter.len(), 4);
+        assert_eq!(iter.next(), Some(1));
+        assert_eq!(iter.next_back(), Some(4));
+        assert_eq!(iter.as_slice(), &[2, 3]);
+        assert_eq!(iter.collect::<std::vec::Vec<_>>(), vec![2, 3]);
+    }
+
+    #[test]
+    fn into_iter_drops_unconsumed_elements_once() {
+        let drops = Cell::new(0);
+        let mut list = Vec::new();
+        for _ in 0..5 {
+            list.push(DropCounter::new(&drops));
+        }
+
+        let mut iter = list.into_iter();
+        drop(iter.next());
+        drop(iter.next_back());
+        assert_eq!(drops.get(), 2);
+
+        drop(iter);
+        assert_eq!(drops.get(), 5);
+    }
+
+    #[test]
+    fn drain_drops_unconsumed_elements_once() {
+        let drops = Cell::new(0);
+        let mut list = Vec::new();
+        for _ in 0..6 {
+            list.push(DropCounter::new(&drops));
+        }
+
+        let mut drain = list.drain(1..5);
+        drop(drain.next());
+        assert_eq!(drops.get(), 1);
+
+        drop(drain);
+        assert_eq!(drops.get(), 4);
+        assert_eq!(list.len(), 2);
+
+        drop(list);
+        assert_eq!(drops.get(), 6);
+    }
+
+    #[test]
+    fn drain_keeps_tail_when_element_drop_panics() {
+        let drops = Cell::new(0);
+        let mut list = Vec::new();
+        list.push(DropCounter::new(&drops));
+        list.push(DropCounter::panicking(&drops));
+        list.push(DropCounter::new(&drops));
+        list.push(DropCounter::new(&drops));
+
+        let result = panic::catch_unwind(AssertUnwindSafe(|| {
+            list.drain(1..3);
+        }));
+
+        assert!(result.is_err());
+        assert_eq!(drops.get(), 2);
+        assert_eq!(list.len(), 2);
+
+        drop(list);
+        assert_eq!(drops.get(), 4);
+    }
+
+    #[test]
+    fn into_iter_drops_remaining_when_element_drop_panics() {
+        let drops = Cell::new(0);
+        let mut list = Vec::new();
+        list.push(DropCounter::panicking(&drops));
+        list.push(DropCounter::new(&drops));
+        list.push(DropCounter::new(&drops));
+
+        let iter = list.into_iter();
+        let result = panic::catch_unwind(AssertUnwindSafe(|| drop(iter)));
+
+        assert!(result.is_err());
+        assert_eq!(drops.get(), 3);
+    }
+
+    fn capacities(strategy: GrowthStrategy, pushes: u32) -> std::vec::Vec<usize> {
+        let mut list = Vec::with_growth_strategy(strategy);
+        let mut caps = std::vec::Vec::new();
+        for i in 0..pushes {
+            list.push(i);
+            if caps.last() != Some(&list.capacity()) {
+                caps.push(list.capacity());
+            }
+        }
+        caps
+    }
+
+    #[test]
+    fn growth_strategies_work() {
+        assert_eq!(
+            capacities(GrowthStrategy::Doubling, 40),
+            vec![4, 8, 16, 32, 64]
+        );
+        assert_eq!(
+            capacities(GrowthStrategy::OneAndHalf, 40),
+            vec![4, 6, 9, 13, 19, 28, 42]
+        );
+        assert_eq!(capacities(GrowthStrategy::Chunk(10), 25), vec![10, 20, 30]);
+        assert_eq!(capacities(GrowthStrategy::Chunk(0), 6), vec![4, 5, 6]);
+
+        let mut list = Vec::with_growth_strategy(GrowthStrategy::Chunk(10));
+        list.extend([1, 2, 3]);
+        assert_eq!(
+            list.split_off(1).growth_strategy(),
+            GrowthStrategy::Chunk(10)
+        );
+    }
+
+    #[test]
+    fn min_non_zero_capacity_depends_on_element_size() {
+        let mut bytes = Vec::new();
+        bytes.push(1u8);
+        assert_eq!(bytes.capacity(), 8);
+
+        let mut words = Vec::new();
+        words.push(1u64);
+        assert_eq!(words.capacity(), 4);
+
+        let mut pages = Vec::new();
+        pages.push([0u8; 2048]);
+        assert_eq!(pages.capacity(), 1);
+    }
+
+    #[test]
+    #[should_panic(expected = "range out of bounds")]
+    fn drain_panics_on_inclusive_usize_max() {
+        let mut vec: Vec<i32> = (0..3).collect();
+        vec.drain(1..=usize::MAX);
+    }
+
+    #[test]
+    #[should_panic(expected = "range out of bounds")]
+    fn drain_panics_on_excluded_usize_max_start() {
+        let mut vec: Vec<i32> = (0..3).collect();
+        vec.drain((Bound::Excluded(usize::MAX), Bound::Unbounded));
+    }
+
+    #[test]
+    fn custom_allocator_works() {
+        let alloc = BudgetAllocator::new(1024);
+        let mut list = Vec::new_in(&alloc);
+        list.extend(0..10u64);
+        assert_eq!(alloc.in_use.get(), 10 * 8);
+
+        let tail = list.split_off(5);
+        assert_eq!(&tail[..], &[5, 6, 7, 8, 9]);
+        assert_eq!(alloc.allocations.get(), 2);
+
+        list.shrink_to_fit();
+        drop(tail);
+        assert_eq!(alloc.in_use.get(), 5 * 8);
+
+        drop(list);
+        assert_eq!(alloc.in_use.get(), 0);
+    }
+
+    #[test]
+    fn try_reserve_reports_failure() {
+        let alloc = BudgetAllocator::new(128);
+        let mut list = Vec::with_capacity_in(4, &alloc);
+        list.extend([1u64, 2, 3, 4]);
+
+        assert_eq!(
+            list.try_reserve(usize::MAX),
+            Err(TryReserveError::CapacityOverflow)
+        );
+        assert_eq!(
+            list.try_reserve_exact(100),
+            Err(TryReserveError::AllocError {
+                layout: Layout::array::<u64>(104).unwrap()
+            })
+        );
+        assert_eq!(list.capacity(), 4);
+        assert_eq!(&list[..], &[1, 2, 3, 4]);
+
+        assert_eq!(list.try_reserve(4), Ok(()));
+        assert_eq!(list.capacity(), 8);
+    }
+
+    #[test]
+    fn compares_and_hashes_as_a_slice() {
+        let list: Vec<i32> = (1..=3).collect();
+        let mut roomy = Vec::with_capacity(16);
+        roomy.extend([1, 2, 3]);
+        assert_eq!(list, roomy);
+        assert_eq!(hash_of(&list), hash_of(&roomy));
+        assert_eq!(hash_of(&list), hash_of(&[1, 2, 3][..]));
+        assert_eq!(format!("{:?}", list), "[1, 2, 3]");
+
+        let mut copy = list.clone();
+        copy[2] = 4;
+        assert_eq!(list.cmp(&copy), std::cmp::Ordering::Less);
+    }
+
+    #[test]
+    fn for_loops_work() {
+        let mut list = Vec::new();
+        list.extend([1, 2, 3]);
+
+        for elem in &mut list {
+            *elem *= 2;
+        }
+
+        let mut sum = 0;
+        for elem in &list {
+            sum += *elem;
+        }
+        assert_eq!(sum, 12);
+    }
+
+    #[test]
+    fn send_and_sync_work() {
+        let mut vec: Vec<String> = Vec::new();
+        vec.push("a".to_string());
+        vec = std::thread::spawn(move || {
+            vec.push("b".to_string());
+            vec
+        })
+        .join()
+        .unwrap();
+
+        let mut drain = vec.drain(..);
+        let first = std::thread::scope(|s| s.spawn(|| drain.next()).join().unwrap());
+        assert_eq!(first.as_deref(), Some("a"));
+        assert_eq!(drain.next().as_deref(), Some("b"));
+    }
 }
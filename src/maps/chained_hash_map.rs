@@ -0,0 +1,436 @@
+use crate::lists::singly_linked_list::{self, SinglyLinkedList};
+use std::borrow::Borrow;
+use std::collections::hash_map::RandomState;
+use std::fmt::{self, Display};
+use std::hash::{BuildHasher, Hash};
+use std::slice;
+
+const INITIAL_BUCKETS: usize = 8;
+
+// Grow once the average chain length exceeds 3/4.
+const MAX_LOAD_NUMERATOR: usize = 3;
+const MAX_LOAD_DENOMINATOR: usize = 4;
+
+pub struct ChainedHashMap<K, V, S = RandomState> {
+    buckets: Vec<SinglyLinkedList<(K, V)>>,
+    len: usize,
+    hash_builder: S,
+}
+
+/// Snapshot of how entries are spread across the buckets of a [`ChainedHashMap`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ChainStats {
+    pub buckets: usize,
+    pub occupied: usize,
+    pub longest_chain: usize,
+    /// `histogram[n]` is the number of buckets holding a chain of length `n`.
+    pub histogram: Vec<usize>,
+}
+
+impl ChainStats {
+    pub fn load_factor(&self) -> f64 {
+        let entries: usize = self
+            .histogram
+            .iter()
+            .enumerate()
+            .map(|(len, count)| len * count)
+            .sum();
+
+        if self.buckets == 0 {
+            0.0
+        } else {
+            entries as f64 / self.buckets as f64
+        }
+    }
+}
+
+impl<K, V> ChainedHashMap<K, V, RandomState> {
+    pub fn new() -> Self {
+        Self::with_hasher(RandomState::new())
+    }
+
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self::with_capacity_and_hasher(capacity, RandomState::new())
+    }
+}
+
+impl<K, V, S> ChainedHashMap<K, V, S> {
+    pub fn with_hasher(hash_builder: S) -> Self {
+        ChainedHashMap {
+            buckets: Vec::new(),
+            len: 0,
+            hash_builder,
+        }
+    }
+
+    pub fn with_capacity_and_hasher(capacity: usize, hash_builder: S) -> Self {
+        let mut map = Self::with_hasher(hash_builder);
+        if capacity > 0 {
+            map.buckets = Self::empty_buckets(Self::buckets_for(capacity));
+        }
+        map
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn bucket_count(&self) -> usize {
+        self.buckets.len()
+    }
+
+    pub fn clear(&mut self) {
+        for bucket in self.buckets.iter_mut() {
+            while bucket.pop_front().is_some() {}
+        }
+        self.len = 0;
+    }
+
+    pub fn iter(&self) -> Iter<'_, K, V> {
+        Iter {
+            buckets: self.buckets.iter(),
+            chain: None,
+            len: self.len,
+        }
+    }
+
+    pub fn keys(&self) -> impl Iterator<Item = &K> {
+        self.iter().map(|(key, _)| key)
+    }
+
+    pub fn values(&self) -> impl Iterator<Item = &V> {
+        self.iter().map(|(_, value)| value)
+    }
+
+    pub fn stats(&self) -> ChainStats {
+        let mut histogram = vec![0; 1];
+        let mut occupied = 0;
+        let mut longest_chain = 0;
+
+        for bucket in self.buckets.iter() {
+            let len = bucket.len();
+            if len >= histogram.len() {
+                histogram.resize(len + 1, 0);
+            }
+            histogram[len] += 1;
+
+            if len > 0 {
+                occupied += 1;
+            }
+            longest_chain = longest_chain.max(len);
+        }
+
+        ChainStats {
+            buckets: self.buckets.len(),
+            occupied,
+            longest_chain,
+            histogram,
+        }
+    }
+
+    fn buckets_for(capacity: usize) -> usize {
+        let needed = capacity * MAX_LOAD_DENOMINATOR / MAX_LOAD_NUMERATOR + 1;
+        needed.next_power_of_two().max(INITIAL_BUCKETS)
+    }
+
+    fn empty_buckets(count: usize) -> Vec<SinglyLinkedList<(K, V)>> {
+        (0..count).map(|_| SinglyLinkedList::new()).collect()
+    }
+}
+
+impl<K, V, S> ChainedHashMap<K, V, S>
+where
+    K: Hash + Eq,
+    S: BuildHasher,
+{
+    pub fn insert(&mut self, key: K, value: V) -> Option<V> {
+        if let Some(existing) = self.get_mut(&key) {
+            return Some(std::mem::replace(existing, value));
+        }
+
+        if (self.len + 1) * MAX_LOAD_DENOMINATOR > self.buckets.len() * MAX_LOAD_NUMERATOR {
+            self.rehash((self.buckets.len() * 2).max(INITIAL_BUCKETS));
+        }
+
+        let index = self.bucket_index(&key);
+        self.buckets[index].push_front((key, value));
+        self.len += 1;
+        None
+    }
+
+    pub fn get<Q>(&self, key: &Q) -> Option<&V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        if self.buckets.is_empty() {
+            return None;
+        }
+
+        self.buckets[self.bucket_index(key)]
+            .iter()
+            .find(|(k, _)| k.borrow() == key)
+            .map(|(_, v)| v)
+    }
+
+    pub fn get_mut<Q>(&mut self, key: &Q) -> Option<&mut V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        if self.buckets.is_empty() {
+            return None;
+        }
+
+        let index = self.bucket_index(key);
+        self.buckets[index]
+            .iter_mut()
+            .find(|(k, _)| k.borrow() == key)
+            .map(|(_, v)| v)
+    }
+
+    pub fn contains_key<Q>(&self, key: &Q) -> bool
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.get(key).is_some()
+    }
+
+    pub fn remove<Q>(&mut self, key: &Q) -> Option<V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        if self.buckets.is_empty() {
+            return None;
+        }
+
+        let index = self.bucket_index(key);
+        let bucket = &mut self.buckets[index];
+        let position = bucket.iter().position(|(k, _)| k.borrow() == key)?;
+
+        self.len -= 1;
+        bucket.remove(position).map(|(_, v)| v)
+    }
+
+    /// Redistributes every entry over at least `bucket_count` buckets. The
+    /// count is raised so the current entries still fit under the maximum
+    /// load factor, then rounded up to a power of two.
+    pub fn rehash(&mut self, bucket_count: usize) {
+        let bucket_count = bucket_count
+            .max(Self::buckets_for(self.len))
+            .next_power_of_two();
+        if bucket_count == self.buckets.len() {
+            return;
+        }
+
+        let old_buckets = std::mem::replace(&mut self.buckets, Self::empty_buckets(bucket_count));
+        for mut bucket in old_buckets {
+            while let Some((key, value)) = bucket.pop_front() {
+                let index = self.bucket_index(&key);
+                self.buckets[index].push_front((key, value));
+            }
+        }
+    }
+
+    pub fn reserve(&mut self, additional: usize) {
+        let needed = Self::buckets_for(self.len + additional);
+        if needed > self.buckets.len() {
+            self.rehash(needed);
+        }
+    }
+
+    pub fn shrink_to_fit(&mut self) {
+        if self.len == 0 {
+            self.buckets = Vec::new();
+        } else {
+            self.rehash(0);
+        }
+    }
+
+    fn bucket_index<Q>(&self, key: &Q) -> usize
+    where
+        Q: Hash + ?Sized,
+    {
+        // Bucket counts are always a power of two.
+        (self.hash_builder.hash_one(key) as usize) & (self.buckets.len() - 1)
+    }
+}
+
+impl<K, V> Default for ChainedHashMap<K, V, RandomState> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+pub struct Iter<'a, K, V> {
+    buckets: slice::Iter<'a, SinglyLinkedList<(K, V)>>,
+    chain: Option<singly_linked_list::Iter<'a, (K, V)>>,
+    len: usize,
+}
+
+impl<'a, K, V> Iterator for Iter<'a, K, V> {
+    type Item = (&'a K, &'a V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some((key, value)) = self.chain.as_mut().and_then(|chain| chain.next()) {
+                self.len -= 1;
+                return Some((key, value));
+            }
+
+            self.chain = Some(self.buckets.next()?.iter());
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.len, Some(self.len))
+    }
+}
+
+impl<K, V> ExactSizeIterator for Iter<'_, K, V> {}
+
+impl<K: Display, V: Display, S> Display for ChainedHashMap<K, V, S> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{{")?;
+        let mut iter = self.iter();
+
+        if let Some((key, value)) = iter.next() {
+            write!(f, "{}: {}", key, value)?;
+
+            for (key, value) in iter {
+                write!(f, ", {}: {}", key, value)?;
+            }
+        }
+
+        write!(f, "}}")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ChainedHashMap;
+
+    #[test]
+    fn insert_works() {
+        let mut map = ChainedHashMap::new();
+        assert_eq!(map.insert("a", 1), None);
+        assert_eq!(map.insert("b", 2), None);
+        assert_eq!(map.insert("a", 3), Some(1));
+        println!("map: {}", map);
+
+        assert_eq!(map.get("a"), Some(&3));
+        assert_eq!(map.get("b"), Some(&2));
+        assert_eq!(map.get("c"), None);
+        assert_eq!(map.len(), 2);
+    }
+
+    #[test]
+    fn get_returns_none_for_empty_map() {
+        let map: ChainedHashMap<i32, i32> = ChainedHashMap::new();
+
+        assert_eq!(map.get(&1), None);
+        assert!(!map.contains_key(&1));
+        assert_eq!(map.len(), 0);
+    }
+
+    #[test]
+    fn get_mut_works() {
+        let mut map = ChainedHashMap::new();
+        map.insert(String::from("a"), vec![1]);
+
+        map.get_mut("a").unwrap().push(2);
+
+        assert_eq!(map.get("a"), Some(&vec![1, 2]));
+    }
+
+    #[test]
+    fn remove_works() {
+        let mut map = ChainedHashMap::new();
+        map.insert(1, "one");
+        map.insert(2, "two");
+        map.insert(3, "three");
+
+        assert_eq!(map.remove(&2), Some("two"));
+        assert_eq!(map.remove(&2), None);
+        assert_eq!(map.get(&1), Some(&"one"));
+        assert_eq!(map.get(&3), Some(&"three"));
+        assert_eq!(map.len(), 2);
+    }
+
+    #[test]
+    fn rehash_keeps_entries() {
+        let mut map = ChainedHashMap::new();
+        for i in 0..1000 {
+            map.insert(i, i * 2);
+        }
+
+        assert!(map.bucket_count() >= 1000);
+        for i in 0..1000 {
+            assert_eq!(map.get(&i), Some(&(i * 2)));
+        }
+
+        for i in 0..990 {
+            map.remove(&i);
+        }
+        map.shrink_to_fit();
+
+        assert!(map.bucket_count() < 1000);
+        for i in 990..1000 {
+            assert_eq!(map.get(&i), Some(&(i * 2)));
+        }
+    }
+
+    #[test]
+    fn rehash_rounds_up_to_a_power_of_two() {
+        let mut map = ChainedHashMap::new();
+        for i in 0..10 {
+            map.insert(i, i);
+        }
+
+        map.rehash(100);
+        assert_eq!(map.bucket_count(), 128);
+        assert!(map.bucket_count().is_power_of_two());
+        for i in 0..10 {
+            assert_eq!(map.get(&i), Some(&i));
+        }
+    }
+
+    #[test]
+    fn iter_works() {
+        let mut map = ChainedHashMap::new();
+        for i in 0..50 {
+            map.insert(i, i + 100);
+        }
+
+        let mut pairs: Vec<(i32, i32)> = map.iter().map(|(k, v)| (*k, *v)).collect();
+        pairs.sort();
+
+        assert_eq!(map.iter().len(), 50);
+        assert_eq!(pairs, (0..50).map(|i| (i, i + 100)).collect::<Vec<_>>());
+        assert_eq!(map.keys().count(), 50);
+        assert_eq!(map.values().sum::<i32>(), (100..150).sum());
+    }
+
+    #[test]
+    fn stats_works() {
+        let mut map = ChainedHashMap::with_capacity(16);
+        for i in 0..10 {
+            map.insert(i, ());
+        }
+
+        let stats = map.stats();
+        println!("stats: {:?}", stats);
+
+        assert_eq!(stats.buckets, map.bucket_count());
+        assert_eq!(stats.histogram.iter().sum::<usize>(), stats.buckets);
+        assert_eq!(stats.histogram.len(), stats.longest_chain + 1);
+        assert_eq!(stats.buckets - stats.histogram[0], stats.occupied);
+        assert!((stats.load_factor() - 10.0 / stats.buckets as f64).abs() < f64::EPSILON);
+    }
+}
@@ -0,0 +1,56 @@
+use std::borrow::Borrow;
+use std::hash::{Hash, Hasher};
+
+// Hash indexes over intrusive nodes point back into the key stored in each node
+// so keys are not duplicated.
+pub(crate) struct KeyRef<K> {
+    pub(crate) key: *const K,
+}
+
+impl<K: Hash> Hash for KeyRef<K> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        unsafe { (*self.key).hash(state) }
+    }
+}
+
+impl<K: PartialEq> PartialEq for KeyRef<K> {
+    fn eq(&self, other: &Self) -> bool {
+        unsafe { (*self.key).eq(&*other.key) }
+    }
+}
+
+impl<K: Eq> Eq for KeyRef<K> {}
+
+// Lets the index be queried with any `Q` the key can be borrowed as.
+#[repr(transparent)]
+pub(crate) struct KeyWrapper<Q: ?Sized>(Q);
+
+impl<Q: ?Sized> KeyWrapper<Q> {
+    pub(crate) fn from_ref(key: &Q) -> &Self {
+        unsafe { &*(key as *const Q as *const KeyWrapper<Q>) }
+    }
+}
+
+impl<Q: Hash + ?Sized> Hash for KeyWrapper<Q> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.0.hash(state)
+    }
+}
+
+impl<Q: PartialEq + ?Sized> PartialEq for KeyWrapper<Q> {
+    fn eq(&self, other: &Self) -> bool {
+        self.0.eq(&other.0)
+    }
+}
+
+impl<Q: Eq + ?Sized> Eq for KeyWrapper<Q> {}
+
+impl<K, Q> Borrow<KeyWrapper<Q>> for KeyRef<K>
+where
+    K: Borrow<Q>,
+    Q: ?Sized,
+{
+    fn borrow(&self) -> &KeyWrapper<Q> {
+        KeyWrapper::from_ref(unsafe { &*self.key }.borrow())
+    }
+}
@@ -0,0 +1,468 @@
+use crate::lists::node_list::{Linked, Links, NodeList};
+use crate::maps::key_ref::{KeyRef, KeyWrapper};
+use crate::maps::ChainedHashMap;
+use std::borrow::Borrow;
+use std::collections::hash_map::RandomState;
+use std::fmt::{self, Display};
+use std::hash::{BuildHasher, Hash};
+use std::marker::PhantomData;
+use std::ptr::{self, NonNull};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Order {
+    /// Entries stay where they were first inserted.
+    Insertion,
+    /// `get`, `get_mut` and re-inserting an existing key move the entry to the back.
+    Access,
+}
+
+struct Node<K, V> {
+    key: K,
+    value: V,
+    links: Links<Node<K, V>>,
+}
+
+impl<K, V> Linked for Node<K, V> {
+    unsafe fn links(node: *mut Self) -> *mut Links<Self> {
+        ptr::addr_of_mut!((*node).links)
+    }
+}
+
+pub struct LinkedHashMap<K, V, S = RandomState> {
+    index: ChainedHashMap<KeyRef<K>, NonNull<Node<K, V>>, S>,
+    list: NodeList<Node<K, V>>,
+    order: Order,
+}
+
+impl<K, V> LinkedHashMap<K, V, RandomState> {
+    pub fn new() -> Self {
+        Self::with_order(Order::Insertion)
+    }
+
+    pub fn with_order(order: Order) -> Self {
+        Self::with_order_and_hasher(order, RandomState::new())
+    }
+}
+
+impl<K, V, S> LinkedHashMap<K, V, S> {
+    pub fn with_order_and_hasher(order: Order, hash_builder: S) -> Self {
+        LinkedHashMap {
+            index: ChainedHashMap::with_hasher(hash_builder),
+            list: NodeList::new(),
+            order,
+        }
+    }
+
+    pub fn order(&self) -> Order {
+        self.order
+    }
+
+    pub fn len(&self) -> usize {
+        self.index.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.index.is_empty()
+    }
+
+    pub fn front(&self) -> Option<(&K, &V)> {
+        self.list.head().map(|node_ptr| unsafe {
+            let node = &*node_ptr.as_ptr();
+            (&node.key, &node.value)
+        })
+    }
+
+    pub fn back(&self) -> Option<(&K, &V)> {
+        self.list.tail().map(|node_ptr| unsafe {
+            let node = &*node_ptr.as_ptr();
+            (&node.key, &node.value)
+        })
+    }
+
+    pub fn iter(&self) -> Iter<'_, K, V> {
+        Iter {
+            head: self.list.head(),
+            tail: self.list.tail(),
+            len: self.len(),
+            marker: PhantomData,
+        }
+    }
+
+    pub fn iter_mut(&mut self) -> IterMut<'_, K, V> {
+        IterMut {
+            head: self.list.head(),
+            tail: self.list.tail(),
+            len: self.len(),
+            marker: PhantomData,
+        }
+    }
+
+    pub fn keys(&self) -> impl DoubleEndedIterator<Item = &K> {
+        self.iter().map(|(key, _)| key)
+    }
+
+    pub fn values(&self) -> impl DoubleEndedIterator<Item = &V> {
+        self.iter().map(|(_, value)| value)
+    }
+
+    fn touch(&mut self, node_ptr: NonNull<Node<K, V>>) {
+        if self.order == Order::Access && self.list.tail() != Some(node_ptr) {
+            unsafe {
+                self.list.unlink(node_ptr);
+                self.list.link_back(node_ptr);
+            }
+        }
+    }
+}
+
+impl<K, V, S> LinkedHashMap<K, V, S>
+where
+    K: Hash + Eq,
+    S: BuildHasher,
+{
+    /// Inserts a new entry at the back. If the key is already present its value
+    /// is replaced; in access order the entry also moves to the back.
+    pub fn insert(&mut self, key: K, value: V) -> Option<V> {
+        if let Some(&node_ptr) = self.index.get(KeyWrapper::from_ref(&key)) {
+            self.touch(node_ptr);
+            let old = unsafe { std::mem::replace(&mut (*node_ptr.as_ptr()).value, value) };
+            return Some(old);
+        }
+
+        let node = Box::new(Node {
+            key,
+            value,
+            links: Links::new(),
+        });
+        let node_ptr = unsafe { NonNull::new_unchecked(Box::into_raw(node)) };
+        let key_ref = KeyRef {
+            key: unsafe { &(*node_ptr.as_ptr()).key },
+        };
+
+        self.index.insert(key_ref, node_ptr);
+        unsafe { self.list.link_back(node_ptr) };
+        None
+    }
+
+    /// Looks up `key`, moving the entry to the back in access order.
+    ///
+    /// The order is chosen at runtime, so this takes `&mut self` even for
+    /// maps in insertion order, where it never reorders anything. Use `peek`
+    /// to look up through a shared reference.
+    pub fn get<Q>(&mut self, key: &Q) -> Option<&V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        let node_ptr = *self.index.get(KeyWrapper::from_ref(key))?;
+        self.touch(node_ptr);
+        Some(unsafe { &(*node_ptr.as_ptr()).value })
+    }
+
+    pub fn get_mut<Q>(&mut self, key: &Q) -> Option<&mut V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        let node_ptr = *self.index.get(KeyWrapper::from_ref(key))?;
+        self.touch(node_ptr);
+        Some(unsafe { &mut (*node_ptr.as_ptr()).value })
+    }
+
+    /// Looks up `key` without changing the iteration order.
+    pub fn peek<Q>(&self, key: &Q) -> Option<&V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        let node_ptr = *self.index.get(KeyWrapper::from_ref(key))?;
+        Some(unsafe { &(*node_ptr.as_ptr()).value })
+    }
+
+    pub fn contains_key<Q>(&self, key: &Q) -> bool
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.index.contains_key(KeyWrapper::from_ref(key))
+    }
+
+    /// Moves the entry to the back regardless of the map's order.
+    pub fn move_to_back<Q>(&mut self, key: &Q) -> bool
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        match self.index.get(KeyWrapper::from_ref(key)) {
+            Some(&node_ptr) => {
+                unsafe {
+                    self.list.unlink(node_ptr);
+                    self.list.link_back(node_ptr);
+                }
+                true
+            }
+            None => false,
+        }
+    }
+
+    pub fn remove<Q>(&mut self, key: &Q) -> Option<V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        let node_ptr = self.index.remove(KeyWrapper::from_ref(key))?;
+        let node = unsafe {
+            self.list.unlink(node_ptr);
+            Box::from_raw(node_ptr.as_ptr())
+        };
+        Some(node.value)
+    }
+
+    pub fn pop_front(&mut self) -> Option<(K, V)> {
+        let node_ptr = self.list.head()?;
+        Some(self.remove_node(node_ptr))
+    }
+
+    pub fn pop_back(&mut self) -> Option<(K, V)> {
+        let node_ptr = self.list.tail()?;
+        Some(self.remove_node(node_ptr))
+    }
+
+    pub fn clear(&mut self) {
+        self.index.clear();
+        self.list = NodeList::new();
+    }
+
+    fn remove_node(&mut self, node_ptr: NonNull<Node<K, V>>) -> (K, V) {
+        unsafe {
+            self.index
+                .remove(KeyWrapper::from_ref(&(*node_ptr.as_ptr()).key));
+        }
+        let node = unsafe {
+            self.list.unlink(node_ptr);
+            Box::from_raw(node_ptr.as_ptr())
+        };
+        (node.key, node.value)
+    }
+}
+
+impl<K, V> Default for LinkedHashMap<K, V, RandomState> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+pub struct Iter<'a, K, V> {
+    head: Option<NonNull<Node<K, V>>>,
+    tail: Option<NonNull<Node<K, V>>>,
+    len: usize,
+    marker: PhantomData<&'a Node<K, V>>,
+}
+
+impl<'a, K, V> Iterator for Iter<'a, K, V> {
+    type Item = (&'a K, &'a V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.len == 0 {
+            return None;
+        }
+
+        self.head.map(|node_ptr| unsafe {
+            let node = &*node_ptr.as_ptr();
+            self.head = node.links.next;
+            self.len -= 1;
+            (&node.key, &node.value)
+        })
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.len, Some(self.len))
+    }
+}
+
+impl<K, V> DoubleEndedIterator for Iter<'_, K, V> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.len == 0 {
+            return None;
+        }
+
+        self.tail.map(|node_ptr| unsafe {
+            let node = &*node_ptr.as_ptr();
+            self.tail = node.links.prev;
+            self.len -= 1;
+            (&node.key, &node.value)
+        })
+    }
+}
+
+impl<K, V> ExactSizeIterator for Iter<'_, K, V> {}
+
+pub struct IterMut<'a, K, V> {
+    head: Option<NonNull<Node<K, V>>>,
+    tail: Option<NonNull<Node<K, V>>>,
+    len: usize,
+    marker: PhantomData<&'a mut Node<K, V>>,
+}
+
+impl<'a, K, V> Iterator for IterMut<'a, K, V> {
+    type Item = (&'a K, &'a mut V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.len == 0 {
+            return None;
+        }
+
+        self.head.map(|node_ptr| unsafe {
+            let node = node_ptr.as_ptr();
+            self.head = (*node).links.next;
+            self.len -= 1;
+            (&(*node).key, &mut (*node).value)
+        })
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.len, Some(self.len))
+    }
+}
+
+impl<K, V> DoubleEndedIterator for IterMut<'_, K, V> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.len == 0 {
+            return None;
+        }
+
+        self.tail.map(|node_ptr| unsafe {
+            let node = node_ptr.as_ptr();
+            self.tail = (*node).links.prev;
+            self.len -= 1;
+            (&(*node).key, &mut (*node).value)
+        })
+    }
+}
+
+impl<K, V> ExactSizeIterator for IterMut<'_, K, V> {}
+
+impl<K: Display, V: Display, S> Display for LinkedHashMap<K, V, S> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{{")?;
+        let mut iter = self.iter();
+
+        if let Some((key, value)) = iter.next() {
+            write!(f, "{}: {}", key, value)?;
+
+            for (key, value) in iter {
+                write!(f, ", {}: {}", key, value)?;
+            }
+        }
+
+        write!(f, "}}")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{LinkedHashMap, Order};
+
+    fn keys<V>(map: &LinkedHashMap<&'static str, V>) -> Vec<&'static str> {
+        map.keys().copied().collect()
+    }
+
+    #[test]
+    fn insertion_order_works() {
+        let mut map = LinkedHashMap::new();
+        map.insert("c", 3);
+        map.insert("a", 1);
+        map.insert("b", 2);
+        println!("map: {}", map);
+
+        assert_eq!(map.insert("c", 30), Some(3));
+        assert_eq!(map.get("a"), Some(&1));
+        assert_eq!(keys(&map), vec!["c", "a", "b"]);
+        assert_eq!(map.front(), Some((&"c", &30)));
+        assert_eq!(map.back(), Some((&"b", &2)));
+        assert_eq!(map.len(), 3);
+    }
+
+    #[test]
+    fn access_order_works() {
+        let mut map = LinkedHashMap::with_order(Order::Access);
+        map.insert("a", 1);
+        map.insert("b", 2);
+        map.insert("c", 3);
+
+        map.get("a");
+        assert_eq!(keys(&map), vec!["b", "c", "a"]);
+
+        map.insert("b", 20);
+        assert_eq!(keys(&map), vec!["c", "a", "b"]);
+
+        assert_eq!(map.peek("c"), Some(&3));
+        assert_eq!(keys(&map), vec!["c", "a", "b"]);
+
+        *map.get_mut("c").unwrap() += 1;
+        assert_eq!(keys(&map), vec!["a", "b", "c"]);
+        assert_eq!(map.peek("c"), Some(&4));
+    }
+
+    #[test]
+    fn remove_works() {
+        let mut map = LinkedHashMap::new();
+        map.insert("a", 1);
+        map.insert("b", 2);
+        map.insert("c", 3);
+
+        assert_eq!(map.remove("b"), Some(2));
+        assert_eq!(map.remove("b"), None);
+        assert_eq!(keys(&map), vec!["a", "c"]);
+
+        assert_eq!(map.pop_front(), Some(("a", 1)));
+        assert_eq!(map.pop_back(), Some(("c", 3)));
+        assert_eq!(map.pop_back(), None);
+        assert!(map.is_empty());
+    }
+
+    #[test]
+    fn move_to_back_works() {
+        let mut map = LinkedHashMap::new();
+        map.insert("a", 1);
+        map.insert("b", 2);
+
+        assert!(map.move_to_back("a"));
+        assert!(!map.move_to_back("z"));
+        assert_eq!(keys(&map), vec!["b", "a"]);
+    }
+
+    #[test]
+    fn iter_works() {
+        let mut map = LinkedHashMap::new();
+        for i in 0..5 {
+            map.insert(i, i.to_string());
+        }
+
+        for (_, value) in map.iter_mut() {
+            value.push('!');
+        }
+
+        let mut iter = map.iter();
+        assert_eq!(iter.len(), 5);
+        assert_eq!(iter.next(), Some((&0, &String::from("0!"))));
+        assert_eq!(iter.next_back(), Some((&4, &String::from("4!"))));
+        assert_eq!(iter.map(|(k, _)| *k).collect::<Vec<_>>(), vec![1, 2, 3]);
+    }
+
+    #[test]
+    fn many_entries_work() {
+        let mut map = LinkedHashMap::new();
+        for i in 0..1000 {
+            map.insert(i, i);
+        }
+        for i in (0..1000).step_by(2) {
+            map.remove(&i);
+        }
+
+        assert_eq!(map.len(), 500);
+        assert!(map.keys().copied().eq((1..1000).step_by(2)));
+        assert!(map.contains_key(&999));
+        assert!(!map.contains_key(&998));
+    }
+}
@@ -0,0 +1,8 @@
+pub mod chained_hash_map;
+pub(crate) mod key_ref;
+pub mod linked_hash_map;
+pub mod tree_map;
+
+pub use self::chained_hash_map::{ChainStats, ChainedHashMap};
+pub use self::linked_hash_map::{LinkedHashMap, Order};
+pub use self::tree_map::TreeMap;
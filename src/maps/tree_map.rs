@@ -0,0 +1,824 @@
+use std::borrow::Borrow;
+use std::cmp::Ordering;
+use std::fmt::{self, Display};
+use std::mem;
+use std::ops::{Bound, RangeBounds};
+
+type Link<K, V> = Option<Box<Node<K, V>>>;
+
+struct Node<K, V> {
+    key: K,
+    value: V,
+    height: usize,
+    left: Link<K, V>,
+    right: Link<K, V>,
+}
+
+impl<K, V> Node<K, V> {
+    fn new(key: K, value: V) -> Self {
+        Node {
+            key,
+            value,
+            height: 1,
+            left: None,
+            right: None,
+        }
+    }
+}
+
+fn height<K, V>(link: &Link<K, V>) -> usize {
+    link.as_ref().map_or(0, |node| node.height)
+}
+
+fn balance_factor<K, V>(node: &Node<K, V>) -> isize {
+    height(&node.left) as isize - height(&node.right) as isize
+}
+
+fn update_height<K, V>(node: &mut Node<K, V>) {
+    node.height = 1 + height(&node.left).max(height(&node.right));
+}
+
+fn rotate_right<K, V>(mut node: Box<Node<K, V>>) -> Box<Node<K, V>> {
+    let mut left = node.left.take().expect("Rotation requires a left child");
+    node.left = left.right.take();
+    update_height(&mut node);
+    left.right = Some(node);
+    update_height(&mut left);
+    left
+}
+
+fn rotate_left<K, V>(mut node: Box<Node<K, V>>) -> Box<Node<K, V>> {
+    let mut right = node.right.take().expect("Rotation requires a right child");
+    node.right = right.left.take();
+    update_height(&mut node);
+    right.left = Some(node);
+    update_height(&mut right);
+    right
+}
+
+fn rebalance<K, V>(mut node: Box<Node<K, V>>) -> Box<Node<K, V>> {
+    update_height(&mut node);
+    let balance = balance_factor(&node);
+
+    if balance > 1 {
+        if node
+            .left
+            .as_ref()
+            .is_some_and(|left| balance_factor(left) < 0)
+        {
+            node.left = node.left.take().map(rotate_left);
+        }
+        return rotate_right(node);
+    }
+
+    if balance < -1 {
+        if node
+            .right
+            .as_ref()
+            .is_some_and(|right| balance_factor(right) > 0)
+        {
+            node.right = node.right.take().map(rotate_right);
+        }
+        return rotate_left(node);
+    }
+
+    node
+}
+
+fn insert<K: Ord, V>(link: Link<K, V>, key: K, value: V) -> (Box<Node<K, V>>, Option<V>) {
+    match link {
+        None => (Box::new(Node::new(key, value)), None),
+        Some(mut node) => {
+            let old = match key.cmp(&node.key) {
+                Ordering::Less => {
+                    let (left, old) = insert(node.left.take(), key, value);
+                    node.left = Some(left);
+                    old
+                }
+                Ordering::Greater => {
+                    let (right, old) = insert(node.right.take(), key, value);
+                    node.right = Some(right);
+                    old
+                }
+                Ordering::Equal => Some(mem::replace(&mut node.value, value)),
+            };
+
+            (rebalance(node), old)
+        }
+    }
+}
+
+fn remove_min<K, V>(mut node: Box<Node<K, V>>) -> (Box<Node<K, V>>, Link<K, V>) {
+    match node.left.take() {
+        None => {
+            let rest = node.right.take();
+            (node, rest)
+        }
+        Some(left) => {
+            let (min, rest) = remove_min(left);
+            node.left = rest;
+            (min, Some(rebalance(node)))
+        }
+    }
+}
+
+fn remove_max<K, V>(mut node: Box<Node<K, V>>) -> (Box<Node<K, V>>, Link<K, V>) {
+    match node.right.take() {
+        None => {
+            let rest = node.left.take();
+            (node, rest)
+        }
+        Some(right) => {
+            let (max, rest) = remove_max(right);
+            node.right = rest;
+            (max, Some(rebalance(node)))
+        }
+    }
+}
+
+fn remove<K, V, Q>(link: &mut Link<K, V>, key: &Q) -> Option<(K, V)>
+where
+    K: Borrow<Q>,
+    Q: Ord + ?Sized,
+{
+    let node = link.as_mut()?;
+    let removed = match key.cmp(node.key.borrow()) {
+        Ordering::Less => remove(&mut node.left, key),
+        Ordering::Greater => remove(&mut node.right, key),
+        Ordering::Equal => {
+            let mut node = link.take()?;
+            *link = match (node.left.take(), node.right.take()) {
+                (None, right) => right,
+                (left, None) => left,
+                (left, Some(right)) => {
+                    let (mut successor, rest) = remove_min(right);
+                    successor.left = left;
+                    successor.right = rest;
+                    Some(rebalance(successor))
+                }
+            };
+            return Some((node.key, node.value));
+        }
+    };
+
+    if removed.is_some() {
+        *link = link.take().map(rebalance);
+    }
+    removed
+}
+
+// Builds a perfectly balanced tree from `len` entries that are already sorted.
+fn build_sorted<K, V, I>(entries: &mut I, len: usize) -> Link<K, V>
+where
+    I: Iterator<Item = (K, V)>,
+{
+    if len == 0 {
+        return None;
+    }
+
+    let left = build_sorted(entries, len / 2);
+    let (key, value) = entries.next()?;
+    let right = build_sorted(entries, len - len / 2 - 1);
+
+    let mut node = Box::new(Node {
+        key,
+        value,
+        height: 1,
+        left,
+        right,
+    });
+    update_height(&mut node);
+    Some(node)
+}
+
+fn into_sorted<K, V>(link: Link<K, V>, entries: &mut Vec<(K, V)>) {
+    if let Some(node) = link {
+        let node = *node;
+        into_sorted(node.left, entries);
+        entries.push((node.key, node.value));
+        into_sorted(node.right, entries);
+    }
+}
+
+pub struct TreeMap<K, V> {
+    root: Link<K, V>,
+    len: usize,
+}
+
+impl<K, V> TreeMap<K, V> {
+    pub fn new() -> Self {
+        TreeMap { root: None, len: 0 }
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn clear(&mut self) {
+        self.root = None;
+        self.len = 0;
+    }
+
+    pub fn first_key_value(&self) -> Option<(&K, &V)> {
+        let mut node = self.root.as_ref()?;
+        while let Some(left) = node.left.as_ref() {
+            node = left;
+        }
+        Some((&node.key, &node.value))
+    }
+
+    pub fn last_key_value(&self) -> Option<(&K, &V)> {
+        let mut node = self.root.as_ref()?;
+        while let Some(right) = node.right.as_ref() {
+            node = right;
+        }
+        Some((&node.key, &node.value))
+    }
+
+    pub fn iter(&self) -> Iter<'_, K, V> {
+        Iter {
+            range: Range::full(&self.root),
+            len: self.len,
+        }
+    }
+
+    pub fn keys(&self) -> impl DoubleEndedIterator<Item = &K> {
+        self.iter().map(|(key, _)| key)
+    }
+
+    pub fn values(&self) -> impl DoubleEndedIterator<Item = &V> {
+        self.iter().map(|(_, value)| value)
+    }
+
+    fn into_sorted_vec(mut self) -> Vec<(K, V)> {
+        let mut entries = Vec::with_capacity(self.len);
+        into_sorted(self.root.take(), &mut entries);
+        self.len = 0;
+        entries
+    }
+
+    fn from_sorted_vec(entries: Vec<(K, V)>) -> Self {
+        let len = entries.len();
+        TreeMap {
+            root: build_sorted(&mut entries.into_iter(), len),
+            len,
+        }
+    }
+}
+
+impl<K: Ord, V> TreeMap<K, V> {
+    pub fn insert(&mut self, key: K, value: V) -> Option<V> {
+        let (root, old) = insert(self.root.take(), key, value);
+        self.root = Some(root);
+        if old.is_none() {
+            self.len += 1;
+        }
+        old
+    }
+
+    pub fn get<Q>(&self, key: &Q) -> Option<&V>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        let mut curr = self.root.as_ref();
+        while let Some(node) = curr {
+            curr = match key.cmp(node.key.borrow()) {
+                Ordering::Less => node.left.as_ref(),
+                Ordering::Greater => node.right.as_ref(),
+                Ordering::Equal => return Some(&node.value),
+            };
+        }
+
+        None
+    }
+
+    pub fn get_mut<Q>(&mut self, key: &Q) -> Option<&mut V>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        let mut curr = self.root.as_mut();
+        while let Some(node) = curr {
+            curr = match key.cmp(node.key.borrow()) {
+                Ordering::Less => node.left.as_mut(),
+                Ordering::Greater => node.right.as_mut(),
+                Ordering::Equal => return Some(&mut node.value),
+            };
+        }
+
+        None
+    }
+
+    pub fn contains_key<Q>(&self, key: &Q) -> bool
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.get(key).is_some()
+    }
+
+    pub fn remove<Q>(&mut self, key: &Q) -> Option<V>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.remove_entry(key).map(|(_, value)| value)
+    }
+
+    pub fn remove_entry<Q>(&mut self, key: &Q) -> Option<(K, V)>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        let removed = remove(&mut self.root, key);
+        if removed.is_some() {
+            self.len -= 1;
+        }
+        removed
+    }
+
+    pub fn pop_first(&mut self) -> Option<(K, V)> {
+        let root = self.root.take()?;
+        let (min, rest) = remove_min(root);
+        self.root = rest;
+        self.len -= 1;
+
+        let min = *min;
+        Some((min.key, min.value))
+    }
+
+    pub fn pop_last(&mut self) -> Option<(K, V)> {
+        let root = self.root.take()?;
+        let (max, rest) = remove_max(root);
+        self.root = rest;
+        self.len -= 1;
+
+        let max = *max;
+        Some((max.key, max.value))
+    }
+
+    /// Iterates over the entries whose keys fall inside `range`, in ascending order.
+    pub fn range<Q, R>(&self, range: R) -> Range<'_, K, V>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+        R: RangeBounds<Q>,
+    {
+        Range::new(&self.root, range.start_bound(), range.end_bound())
+    }
+
+    /// Splits the map in two at `key`, returning everything greater than or
+    /// equal to `key` and keeping the rest.
+    ///
+    /// This takes O(n) time: the tree is flattened and both halves are rebuilt,
+    /// since nodes don't track subtree sizes and the halves' lengths would
+    /// have to be counted anyway.
+    pub fn split_off<Q>(&mut self, key: &Q) -> Self
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        let mut entries = mem::take(self).into_sorted_vec();
+        let at = entries.partition_point(|(k, _)| k.borrow() < key);
+        let right = entries.split_off(at);
+
+        *self = Self::from_sorted_vec(entries);
+        Self::from_sorted_vec(right)
+    }
+
+    /// Moves every entry of `other` into `self`, leaving `other` empty. Values
+    /// from `other` win when both maps hold the same key.
+    ///
+    /// This takes O(n + m) time: both trees are flattened, merged and rebuilt.
+    pub fn append(&mut self, other: &mut Self) {
+        if other.is_empty() {
+            return;
+        }
+
+        let left = mem::take(self).into_sorted_vec();
+        let right = mem::take(other).into_sorted_vec();
+        let mut merged = Vec::with_capacity(left.len() + right.len());
+
+        let mut left = left.into_iter().peekable();
+        let mut right = right.into_iter().peekable();
+        loop {
+            let next = match (left.peek(), right.peek()) {
+                (Some((l, _)), Some((r, _))) => match l.cmp(r) {
+                    Ordering::Less => left.next(),
+                    Ordering::Greater => right.next(),
+                    Ordering::Equal => {
+                        left.next();
+                        right.next()
+                    }
+                },
+                (Some(_), None) => left.next(),
+                (None, Some(_)) => right.next(),
+                (None, None) => break,
+            };
+            merged.extend(next);
+        }
+
+        *self = Self::from_sorted_vec(merged);
+    }
+}
+
+impl<K, V> Default for TreeMap<K, V> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K, V> IntoIterator for TreeMap<K, V> {
+    type Item = (K, V);
+    type IntoIter = IntoIter<K, V>;
+
+    fn into_iter(self) -> IntoIter<K, V> {
+        IntoIter {
+            entries: self.into_sorted_vec().into_iter(),
+        }
+    }
+}
+
+impl<'a, K, V> IntoIterator for &'a TreeMap<K, V> {
+    type Item = (&'a K, &'a V);
+    type IntoIter = Iter<'a, K, V>;
+
+    fn into_iter(self) -> Iter<'a, K, V> {
+        self.iter()
+    }
+}
+
+pub struct Range<'a, K, V> {
+    // Each stack holds the path to the next entry to yield from that end.
+    front: Vec<&'a Node<K, V>>,
+    back: Vec<&'a Node<K, V>>,
+}
+
+impl<'a, K, V> Range<'a, K, V> {
+    fn full(root: &'a Link<K, V>) -> Self {
+        let mut range = Range {
+            front: Vec::new(),
+            back: Vec::new(),
+        };
+        range.push_left_spine(root.as_deref());
+        range.push_right_spine(root.as_deref());
+        range
+    }
+
+    fn new<Q>(root: &'a Link<K, V>, start: Bound<&Q>, end: Bound<&Q>) -> Self
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        let mut range = Range {
+            front: Vec::new(),
+            back: Vec::new(),
+        };
+
+        let mut curr = root.as_deref();
+        while let Some(node) = curr {
+            let after_start = match start {
+                Bound::Included(start) => node.key.borrow() >= start,
+                Bound::Excluded(start) => node.key.borrow() > start,
+                Bound::Unbounded => true,
+            };
+            if after_start {
+                range.front.push(node);
+                curr = node.left.as_deref();
+            } else {
+                curr = node.right.as_deref();
+            }
+        }
+
+        let mut curr = root.as_deref();
+        while let Some(node) = curr {
+            let before_end = match end {
+                Bound::Included(end) => node.key.borrow() <= end,
+                Bound::Excluded(end) => node.key.borrow() < end,
+                Bound::Unbounded => true,
+            };
+            if before_end {
+                range.back.push(node);
+                curr = node.right.as_deref();
+            } else {
+                curr = node.left.as_deref();
+            }
+        }
+
+        range
+    }
+
+    fn push_left_spine(&mut self, mut curr: Option<&'a Node<K, V>>) {
+        while let Some(node) = curr {
+            self.front.push(node);
+            curr = node.left.as_deref();
+        }
+    }
+
+    fn push_right_spine(&mut self, mut curr: Option<&'a Node<K, V>>) {
+        while let Some(node) = curr {
+            self.back.push(node);
+            curr = node.right.as_deref();
+        }
+    }
+
+    // Returns false once the two ends have crossed, clearing both stacks.
+    fn in_bounds(&mut self) -> bool
+    where
+        K: Ord,
+    {
+        match (self.front.last(), self.back.last()) {
+            (Some(front), Some(back)) if front.key <= back.key => true,
+            _ => {
+                self.front.clear();
+                self.back.clear();
+                false
+            }
+        }
+    }
+
+    fn finish_if_met(&mut self, node: &Node<K, V>) {
+        let met = self
+            .front
+            .last()
+            .zip(self.back.last())
+            .is_some_and(|(front, back)| std::ptr::eq(*front, *back) && std::ptr::eq(*front, node));
+        if met {
+            self.front.clear();
+            self.back.clear();
+        }
+    }
+}
+
+impl<'a, K: Ord, V> Iterator for Range<'a, K, V> {
+    type Item = (&'a K, &'a V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if !self.in_bounds() {
+            return None;
+        }
+
+        let node = *self.front.last()?;
+        self.finish_if_met(node);
+        self.front.pop();
+        self.push_left_spine(node.right.as_deref());
+        Some((&node.key, &node.value))
+    }
+}
+
+impl<K: Ord, V> DoubleEndedIterator for Range<'_, K, V> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if !self.in_bounds() {
+            return None;
+        }
+
+        let node = *self.back.last()?;
+        self.finish_if_met(node);
+        self.back.pop();
+        self.push_right_spine(node.left.as_deref());
+        Some((&node.key, &node.value))
+    }
+}
+
+pub struct Iter<'a, K, V> {
+    range: Range<'a, K, V>,
+    len: usize,
+}
+
+impl<'a, K, V> Iterator for Iter<'a, K, V> {
+    type Item = (&'a K, &'a V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.len == 0 {
+            return None;
+        }
+
+        // The length alone tells the two ends apart, so no key comparisons are needed.
+        let node = self.range.front.pop()?;
+        self.range.push_left_spine(node.right.as_deref());
+        self.len -= 1;
+        Some((&node.key, &node.value))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.len, Some(self.len))
+    }
+}
+
+impl<K, V> DoubleEndedIterator for Iter<'_, K, V> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.len == 0 {
+            return None;
+        }
+
+        let node = self.range.back.pop()?;
+        self.range.push_right_spine(node.left.as_deref());
+        self.len -= 1;
+        Some((&node.key, &node.value))
+    }
+}
+
+impl<K, V> ExactSizeIterator for Iter<'_, K, V> {}
+
+pub struct IntoIter<K, V> {
+    entries: std::vec::IntoIter<(K, V)>,
+}
+
+impl<K, V> Iterator for IntoIter<K, V> {
+    type Item = (K, V);
+
+    fn next(&mut self) -> Option<(K, V)> {
+        self.entries.next()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.entries.size_hint()
+    }
+}
+
+impl<K, V> DoubleEndedIterator for IntoIter<K, V> {
+    fn next_back(&mut self) -> Option<(K, V)> {
+        self.entries.next_back()
+    }
+}
+
+impl<K, V> ExactSizeIterator for IntoIter<K, V> {}
+
+impl<K: Display, V: Display> Display for TreeMap<K, V> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{{")?;
+        let mut iter = self.iter();
+
+        if let Some((key, value)) = iter.next() {
+            write!(f, "{}: {}", key, value)?;
+
+            for (key, value) in iter {
+                write!(f, ", {}: {}", key, value)?;
+            }
+        }
+
+        write!(f, "}}")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Link, TreeMap};
+
+    fn assert_balanced<K: Ord, V>(link: &Link<K, V>) -> usize {
+        match link {
+            None => 0,
+            Some(node) => {
+                let left = assert_balanced(&node.left);
+                let right = assert_balanced(&node.right);
+                assert!(left.abs_diff(right) <= 1, "Tree is out of balance");
+                assert_eq!(node.height, 1 + left.max(right));
+                node.height
+            }
+        }
+    }
+
+    #[test]
+    fn insert_works() {
+        let mut map = TreeMap::new();
+        assert_eq!(map.insert(3, "c"), None);
+        assert_eq!(map.insert(1, "a"), None);
+        assert_eq!(map.insert(2, "b"), None);
+        assert_eq!(map.insert(1, "z"), Some("a"));
+        println!("map: {}", map);
+
+        assert_eq!(map.get(&1), Some(&"z"));
+        assert_eq!(map.get(&4), None);
+        assert_eq!(map.len(), 3);
+        assert_eq!(map.keys().copied().collect::<Vec<_>>(), vec![1, 2, 3]);
+    }
+
+    #[test]
+    fn stays_balanced() {
+        let mut map = TreeMap::new();
+        for i in 0..1000 {
+            map.insert(i, i);
+        }
+        assert!(assert_balanced(&map.root) <= 15);
+
+        for i in (0..1000).filter(|i| i % 3 != 0) {
+            assert_eq!(map.remove(&i), Some(i));
+        }
+        assert_balanced(&map.root);
+
+        assert_eq!(map.len(), 334);
+        assert!(map.keys().copied().eq((0..1000).step_by(3)));
+    }
+
+    #[test]
+    fn get_mut_works() {
+        let mut map = TreeMap::new();
+        map.insert(String::from("a"), 1);
+
+        *map.get_mut("a").unwrap() += 1;
+
+        assert_eq!(map.get("a"), Some(&2));
+    }
+
+    #[test]
+    fn first_and_last_work() {
+        let mut map = TreeMap::new();
+        assert_eq!(map.first_key_value(), None);
+
+        for i in [5, 3, 8, 1, 9] {
+            map.insert(i, i * 10);
+        }
+
+        assert_eq!(map.first_key_value(), Some((&1, &10)));
+        assert_eq!(map.last_key_value(), Some((&9, &90)));
+        assert_eq!(map.pop_first(), Some((1, 10)));
+        assert_eq!(map.pop_last(), Some((9, 90)));
+        assert_eq!(map.len(), 3);
+        assert_balanced(&map.root);
+    }
+
+    #[test]
+    fn iter_works() {
+        let mut map = TreeMap::new();
+        for i in [4, 2, 6, 1, 3, 5, 7] {
+            map.insert(i, ());
+        }
+
+        let mut iter = map.iter();
+        assert_eq!(iter.len(), 7);
+        assert_eq!(iter.next().map(|(k, _)| *k), Some(1));
+        assert_eq!(iter.next_back().map(|(k, _)| *k), Some(7));
+        assert_eq!(
+            iter.map(|(k, _)| *k).collect::<Vec<_>>(),
+            vec![2, 3, 4, 5, 6]
+        );
+
+        let owned: Vec<_> = map.into_iter().rev().map(|(k, _)| k).collect();
+        assert_eq!(owned, vec![7, 6, 5, 4, 3, 2, 1]);
+    }
+
+    #[test]
+    fn range_works() {
+        let mut map = TreeMap::new();
+        for i in 0..20 {
+            map.insert(i * 2, ());
+        }
+
+        let keys = |iter: super::Range<'_, i32, ()>| iter.map(|(k, _)| *k).collect::<Vec<_>>();
+        assert_eq!(keys(map.range(5..12)), vec![6, 8, 10]);
+        assert_eq!(keys(map.range(6..=12)), vec![6, 8, 10, 12]);
+        assert_eq!(keys(map.range(..4)), vec![0, 2]);
+        assert_eq!(keys(map.range(35..)), vec![36, 38]);
+        assert_eq!(keys(map.range(7..8)), Vec::<i32>::new());
+        assert_eq!(keys(map.range(100..)), Vec::<i32>::new());
+
+        let mut range = map.range(10..=16);
+        assert_eq!(range.next_back().map(|(k, _)| *k), Some(16));
+        assert_eq!(range.next().map(|(k, _)| *k), Some(10));
+        assert_eq!(range.next_back().map(|(k, _)| *k), Some(14));
+        assert_eq!(range.next().map(|(k, _)| *k), Some(12));
+        assert_eq!(range.next(), None);
+        assert_eq!(range.next_back(), None);
+    }
+
+    #[test]
+    fn split_off_works() {
+        let mut map = TreeMap::new();
+        for i in 0..10 {
+            map.insert(i, i);
+        }
+
+        let right = map.split_off(&6);
+
+        assert!(map.keys().copied().eq(0..6));
+        assert!(right.keys().copied().eq(6..10));
+        assert_eq!(map.len(), 6);
+        assert_eq!(right.len(), 4);
+        assert_balanced(&map.root);
+        assert_balanced(&right.root);
+    }
+
+    #[test]
+    fn append_works() {
+        let mut left = TreeMap::new();
+        let mut right = TreeMap::new();
+        for i in 0..10 {
+            left.insert(i, "left");
+            right.insert(i + 5, "right");
+        }
+
+        left.append(&mut right);
+
+        assert!(right.is_empty());
+        assert_eq!(left.len(), 15);
+        assert!(left.keys().copied().eq(0..15));
+        assert_eq!(left.get(&4), Some(&"left"));
+        assert_eq!(left.get(&5), Some(&"right"));
+        assert_balanced(&left.root);
+    }
+}
@@ -0,0 +1,19 @@
+use std::ops::Deref;
+
+// Keeps a value on its own cache line so that atomics written by different
+// threads don't invalidate each other's lines. 128 bytes covers the adjacent
+// line prefetcher on x86_64 and the larger lines on Apple silicon.
+#[cfg_attr(any(target_arch = "x86_64", target_arch = "aarch64"), repr(align(128)))]
+#[cfg_attr(
+    not(any(target_arch = "x86_64", target_arch = "aarch64")),
+    repr(align(64))
+)]
+pub(crate) struct CachePadded<T>(pub(crate) T);
+
+impl<T> Deref for CachePadded<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.0
+    }
+}
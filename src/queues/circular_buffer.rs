@@ -1,45 +1,168 @@
-use std::fmt::{self, Display};
+use std::cmp::Ordering;
+use std::fmt::{self, Debug, Display};
+use std::hash::{Hash, Hasher};
+use std::marker::PhantomData;
+use std::mem::MaybeUninit;
+use std::ops::Index;
 
-pub struct CircularBuffer<T: Copy + PartialEq> {
-    buffer: Vec<Option<T>>,
-    len: usize,
+mod sealed {
+    use std::mem::MaybeUninit;
+
+    /// Where a `RingBuffer` keeps its slots. Sealed, so the slot count
+    /// can be trusted never to change.
+    pub trait Slots<T> {
+        fn slots(&self) -> &[MaybeUninit<T>];
+        fn slots_mut(&mut self) -> &mut [MaybeUninit<T>];
+    }
+
+    impl<T> Slots<T> for Box<[MaybeUninit<T>]> {
+        fn slots(&self) -> &[MaybeUninit<T>] {
+            self
+        }
+
+        fn slots_mut(&mut self) -> &mut [MaybeUninit<T>] {
+            self
+        }
+    }
+
+    impl<T, const N: usize> Slots<T> for [MaybeUninit<T>; N] {
+        fn slots(&self) -> &[MaybeUninit<T>] {
+            self
+        }
+
+        fn slots_mut(&mut self) -> &mut [MaybeUninit<T>] {
+            self
+        }
+    }
+}
+
+use self::sealed::Slots;
+
+/// A fixed-capacity FIFO queue over a ring of slots.
+///
+/// Use it as a `CircularBuffer`, which stores its slots inline and never
+/// allocates, or as a `HeapCircularBuffer`, whose capacity is chosen at
+/// runtime. The `len` slots starting at `head` (wrapping) are initialized;
+/// the rest are not.
+pub struct RingBuffer<T, S: Slots<T>> {
+    slots: S,
     head: usize,
-    tail: usize,
+    len: usize,
+    marker: PhantomData<T>,
 }
 
-impl<T: Copy + PartialEq> CircularBuffer<T> {
+pub type CircularBuffer<T, const N: usize> = RingBuffer<T, [MaybeUninit<T>; N]>;
+
+pub type HeapCircularBuffer<T> = RingBuffer<T, Box<[MaybeUninit<T>]>>;
+
+impl<T> HeapCircularBuffer<T> {
     pub fn new(capacity: usize) -> Self {
-        CircularBuffer {
-            buffer: vec![None; capacity],
+        RingBuffer {
+            slots: Box::new_uninit_slice(capacity),
+            head: 0,
             len: 0,
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<T, const N: usize> CircularBuffer<T, N> {
+    pub const fn new() -> Self {
+        RingBuffer {
+            slots: [const { MaybeUninit::uninit() }; N],
             head: 0,
-            tail: 0,
+            len: 0,
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<T, const N: usize> Default for CircularBuffer<T, N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T, S: Slots<T>> RingBuffer<T, S> {
+    pub fn capacity(&self) -> usize {
+        self.slots.slots().len()
+    }
+
+    // Maps a logical index onto its slot, without overflowing on huge
+    // capacities of zero-sized types.
+    fn physical(&self, index: usize) -> usize {
+        let to_end = self.capacity() - self.head;
+        if index >= to_end {
+            index - to_end
+        } else {
+            self.head + index
         }
     }
 
+    /// Panics if the buffer is full; see `try_enqueue` and `push_overwrite`.
     pub fn enqueue(&mut self, elt: T) {
-        if self.is_full() {
+        if self.try_enqueue(elt).is_err() {
             panic!("Buffer is full. Item not added")
         }
+    }
+
+    /// Hands the element back if the buffer is full.
+    pub fn try_enqueue(&mut self, elt: T) -> Result<(), T> {
+        if self.is_full() {
+            return Err(elt);
+        }
 
-        self.buffer[self.tail] = Some(elt);
-        self.tail = (self.tail + 1) % self.buffer.len();
+        let tail = self.physical(self.len);
+        self.slots.slots_mut()[tail].write(elt);
         self.len += 1;
+        Ok(())
+    }
+
+    /// Enqueues the element, evicting and returning the oldest one if the
+    /// buffer is full. A zero-capacity buffer returns the element itself.
+    pub fn push_overwrite(&mut self, elt: T) -> Option<T> {
+        if self.capacity() == 0 {
+            return Some(elt);
+        }
+
+        let evicted = if self.is_full() { self.dequeue() } else { None };
+        if self.try_enqueue(elt).is_err() {
+            unreachable!("a slot was just freed");
+        }
+        evicted
     }
 
     pub fn dequeue(&mut self) -> Option<T> {
-        if self.is_empty() {
+        if self.len == 0 {
             return None;
         }
 
-        let element = self.buffer[self.head].take();
-        self.head = (self.head + 1) % self.buffer.len();
+        let elt = unsafe { self.slots.slots()[self.head].assume_init_read() };
+        self.head = self.physical(1);
         self.len -= 1;
-        element
+        Some(elt)
     }
 
     pub fn top(&self) -> Option<&T> {
-        self.buffer[self.head].as_ref()
+        self.get(0)
+    }
+
+    /// The most recently enqueued element.
+    pub fn back(&self) -> Option<&T> {
+        self.len.checked_sub(1).and_then(|index| self.get(index))
+    }
+
+    /// Counts from the front of the queue, not from the start of the buffer.
+    pub fn get(&self, index: usize) -> Option<&T> {
+        if index >= self.len {
+            return None;
+        }
+        unsafe { Some(self.slots.slots()[self.physical(index)].assume_init_ref()) }
+    }
+
+    pub fn clear(&mut self) {
+        while self.dequeue().is_some() {}
+        self.head = 0;
     }
 
     pub fn len(&self) -> usize {
@@ -51,26 +174,134 @@ impl<T: Copy + PartialEq> CircularBuffer<T> {
     }
 
     pub fn is_full(&self) -> bool {
-        self.len == self.buffer.len()
+        self.len == self.capacity()
+    }
+
+    /// Iterates in queue order, oldest first.
+    pub fn iter(&self) -> Iter<'_, T> {
+        Iter {
+            slots: self.slots.slots(),
+            head: self.head,
+            front: 0,
+            back: self.len,
+        }
     }
 
-    pub fn collect(&self) -> Vec<T> {
-        self.buffer
-            .iter()
-            .filter(|elm| elm.is_some())
-            .map(|elm| elm.unwrap())
-            .collect::<Vec<T>>()
+    pub fn collect(&self) -> Vec<T>
+    where
+        T: Clone,
+    {
+        self.iter().cloned().collect()
     }
 }
 
-impl<T: Copy + PartialEq + Display> Display for CircularBuffer<T> {
+impl<T, S: Slots<T>> Drop for RingBuffer<T, S> {
+    fn drop(&mut self) {
+        self.clear();
+    }
+}
+
+/// Sized to hold exactly the collected elements.
+impl<T> FromIterator<T> for HeapCircularBuffer<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let elements: Vec<T> = iter.into_iter().collect();
+        let mut queue = HeapCircularBuffer::new(elements.len());
+        queue.extend(elements);
+        queue
+    }
+}
+
+/// Panics if more than `N` elements are collected, like `enqueue`.
+impl<T, const N: usize> FromIterator<T> for CircularBuffer<T, N> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut queue = CircularBuffer::new();
+        queue.extend(iter);
+        queue
+    }
+}
+
+impl<T: Clone> Clone for HeapCircularBuffer<T> {
+    fn clone(&self) -> Self {
+        let mut queue = HeapCircularBuffer::new(self.capacity());
+        queue.extend(self.iter().cloned());
+        queue
+    }
+}
+
+impl<T: Clone, const N: usize> Clone for CircularBuffer<T, N> {
+    fn clone(&self) -> Self {
+        self.iter().cloned().collect()
+    }
+}
+
+/// Panics if the buffer fills up, like `enqueue`.
+impl<T, S: Slots<T>> Extend<T> for RingBuffer<T, S> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for elt in iter {
+            self.enqueue(elt);
+        }
+    }
+}
+
+/// Counts from the front of the queue, not from the start of the buffer.
+impl<T, S: Slots<T>> Index<usize> for RingBuffer<T, S> {
+    type Output = T;
+
+    fn index(&self, index: usize) -> &T {
+        self.get(index).expect("Index out of bounds")
+    }
+}
+
+impl<'a, T, S: Slots<T>> IntoIterator for &'a RingBuffer<T, S> {
+    type Item = &'a T;
+    type IntoIter = Iter<'a, T>;
+
+    fn into_iter(self) -> Iter<'a, T> {
+        self.iter()
+    }
+}
+
+impl<T: Debug, S: Slots<T>> Debug for RingBuffer<T, S> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+// Comparisons only look at the queued elements, so two buffers holding the
+// same sequence are equal regardless of capacity or where the head sits.
+impl<T: PartialEq, S: Slots<T>> PartialEq for RingBuffer<T, S> {
+    fn eq(&self, other: &Self) -> bool {
+        self.len() == other.len() && self.iter().eq(other.iter())
+    }
+}
+
+impl<T: Eq, S: Slots<T>> Eq for RingBuffer<T, S> {}
+
+impl<T: Hash, S: Slots<T>> Hash for RingBuffer<T, S> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.len().hash(state);
+        for elt in self.iter() {
+            elt.hash(state);
+        }
+    }
+}
+
+impl<T: PartialOrd, S: Slots<T>> PartialOrd for RingBuffer<T, S> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        self.iter().partial_cmp(other.iter())
+    }
+}
+
+impl<T: Ord, S: Slots<T>> Ord for RingBuffer<T, S> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.iter().cmp(other.iter())
+    }
+}
+
+impl<T: Display, S: Slots<T>> Display for RingBuffer<T, S> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(f, "[")?;
-        let mut iter = self
-            .buffer
-            .iter()
-            .filter(|x| x.is_some())
-            .map(|x| x.unwrap());
+        let mut iter = self.iter();
 
         if let Some(value) = iter.next() {
             write!(f, "{}", value)?;
@@ -84,13 +315,66 @@ impl<T: Copy + PartialEq + Display> Display for CircularBuffer<T> {
     }
 }
 
+pub struct Iter<'a, T> {
+    slots: &'a [MaybeUninit<T>],
+    head: usize,
+    // Logical positions of the next elements to yield from either end.
+    front: usize,
+    back: usize,
+}
+
+impl<'a, T> Iter<'a, T> {
+    fn slot(&self, index: usize) -> &'a T {
+        let to_end = self.slots.len() - self.head;
+        let slot = if index >= to_end {
+            index - to_end
+        } else {
+            self.head + index
+        };
+        unsafe { self.slots[slot].assume_init_ref() }
+    }
+}
+
+impl<'a, T> Iterator for Iter<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<&'a T> {
+        if self.front == self.back {
+            return None;
+        }
+
+        self.front += 1;
+        Some(self.slot(self.front - 1))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.back - self.front;
+        (len, Some(len))
+    }
+}
+
+impl<'a, T> DoubleEndedIterator for Iter<'a, T> {
+    fn next_back(&mut self) -> Option<&'a T> {
+        if self.front == self.back {
+            return None;
+        }
+
+        self.back -= 1;
+        Some(self.slot(self.back))
+    }
+}
+
+impl<T> ExactSizeIterator for Iter<'_, T> {}
+
 #[cfg(test)]
 mod tests {
-    use super::CircularBuffer;
+    use super::{CircularBuffer, HeapCircularBuffer};
+    use crate::test_util::{hash_of, DropCounter};
+    use std::cell::Cell;
 
     #[test]
     fn enqueue_works() {
-        let mut queue = CircularBuffer::new(3);
+        let mut queue = HeapCircularBuffer::new(3);
         queue.enqueue(1);
         queue.enqueue(2);
         queue.enqueue(3);
@@ -103,7 +387,7 @@ mod tests {
 
     #[test]
     fn dequeue_returns_none_for_empty_list() {
-        let mut queue: CircularBuffer<i32> = CircularBuffer::new(3);
+        let mut queue: HeapCircularBuffer<i32> = HeapCircularBuffer::new(3);
         println!("list: {}", queue);
 
         assert_eq!(queue.dequeue(), None);
@@ -112,7 +396,7 @@ mod tests {
 
     #[test]
     fn dequeue_works() {
-        let mut queue = CircularBuffer::new(3);
+        let mut queue = HeapCircularBuffer::new(3);
         queue.enqueue(1);
         queue.enqueue(2);
         queue.enqueue(3);
@@ -138,4 +422,191 @@ mod tests {
 
         assert_eq!(queue.len(), 0);
     }
+
+    #[test]
+    fn std_traits_work() {
+        let queue: HeapCircularBuffer<i32> = (1..=3).collect();
+        assert!(queue.is_full());
+        assert_eq!(format!("{:?}", queue), "[1, 2, 3]");
+
+        // Same elements, but wrapped around a larger buffer.
+        let mut wrapped = HeapCircularBuffer::new(4);
+        wrapped.extend([0, 0, 0]);
+        wrapped.dequeue();
+        wrapped.dequeue();
+        wrapped.dequeue();
+        wrapped.extend([1, 2, 3]);
+        assert_eq!(wrapped[0], 1);
+        assert_eq!(wrapped[2], 3);
+        assert_eq!(format!("{:?}", wrapped), "[1, 2, 3]");
+        assert_eq!(queue, wrapped);
+        assert_eq!(hash_of(&queue), hash_of(&wrapped));
+
+        let mut copy = wrapped.clone();
+        copy.dequeue();
+        assert_ne!(copy, wrapped);
+        assert!(wrapped < copy);
+    }
+
+    #[test]
+    #[should_panic(expected = "Index out of bounds")]
+    fn index_panics_past_len() {
+        let mut queue = HeapCircularBuffer::new(3);
+        queue.enqueue(1);
+        let _ = queue[1];
+    }
+
+    #[test]
+    fn iterates_in_queue_order_after_wrapping() {
+        let mut queue = HeapCircularBuffer::new(3);
+        queue.extend([1, 2, 3]);
+        queue.dequeue();
+        queue.dequeue();
+        queue.extend([4, 5]);
+
+        assert_eq!(queue.collect(), vec![3, 4, 5]);
+        assert_eq!(format!("{}", queue), "[345]");
+        assert!(queue.iter().rev().eq(&[5, 4, 3]));
+        assert_eq!(queue.iter().len(), 3);
+        assert_eq!(queue.top(), Some(&3));
+        assert_eq!(queue.back(), Some(&5));
+        assert_eq!(queue.get(1), Some(&4));
+        assert_eq!(queue.get(3), None);
+    }
+
+    #[test]
+    fn push_overwrite_evicts_the_oldest() {
+        let mut queue = HeapCircularBuffer::new(3);
+        assert_eq!(queue.push_overwrite(1), None);
+        assert_eq!(queue.push_overwrite(2), None);
+        assert_eq!(queue.push_overwrite(3), None);
+        assert_eq!(queue.push_overwrite(4), Some(1));
+        assert_eq!(queue.push_overwrite(5), Some(2));
+        assert_eq!(queue.collect(), vec![3, 4, 5]);
+
+        let mut empty = HeapCircularBuffer::new(0);
+        assert_eq!(empty.push_overwrite(1), Some(1));
+        assert!(empty.is_empty());
+    }
+
+    #[test]
+    fn try_enqueue_hands_back_when_full() {
+        let mut queue = HeapCircularBuffer::new(2);
+        assert_eq!(queue.try_enqueue(1), Ok(()));
+        assert_eq!(queue.try_enqueue(2), Ok(()));
+        assert_eq!(queue.try_enqueue(3), Err(3));
+        assert_eq!(queue.collect(), vec![1, 2]);
+    }
+
+    #[test]
+    fn clear_works() {
+        let mut queue = HeapCircularBuffer::new(3);
+        queue.extend([1, 2, 3]);
+        queue.dequeue();
+        queue.enqueue(4);
+        queue.clear();
+        assert!(queue.is_empty());
+        assert_eq!(queue.top(), None);
+        assert_eq!(queue.back(), None);
+
+        queue.extend([5, 6, 7]);
+        assert_eq!(queue.collect(), vec![5, 6, 7]);
+        assert_eq!(queue.capacity(), 3);
+    }
+
+    #[test]
+    fn holds_owned_elements() {
+        let mut queue = HeapCircularBuffer::new(2);
+        queue.enqueue(String::from("a"));
+        queue.enqueue(String::from("b"));
+        assert_eq!(
+            queue.push_overwrite(String::from("c")),
+            Some(String::from("a"))
+        );
+        assert_eq!(queue.dequeue().as_deref(), Some("b"));
+        queue.enqueue(String::from("d"));
+        assert_eq!(queue.collect(), vec!["c", "d"]);
+
+        let frames: HeapCircularBuffer<Vec<u8>> = vec![vec![1, 2], vec![3]].into_iter().collect();
+        assert_eq!(frames.clone(), frames);
+        assert_eq!(frames[1], vec![3]);
+    }
+
+    #[test]
+    fn drops_each_element_once() {
+        let drops = Cell::new(0);
+        {
+            let mut queue = HeapCircularBuffer::new(3);
+            for _ in 0..3 {
+                queue.enqueue(DropCounter::new(&drops));
+            }
+            drop(queue.dequeue());
+            assert_eq!(drops.get(), 1);
+
+            queue.enqueue(DropCounter::new(&drops));
+            drop(queue.push_overwrite(DropCounter::new(&drops)));
+            assert_eq!(drops.get(), 2);
+
+            queue.clear();
+            assert_eq!(drops.get(), 5);
+
+            queue.enqueue(DropCounter::new(&drops));
+            queue.enqueue(DropCounter::new(&drops));
+        }
+        assert_eq!(drops.get(), 7);
+
+        {
+            let mut queue: CircularBuffer<_, 2> = CircularBuffer::new();
+            queue.enqueue(DropCounter::new(&drops));
+            drop(queue.push_overwrite(DropCounter::new(&drops)));
+            drop(queue.push_overwrite(DropCounter::new(&drops)));
+            assert_eq!(drops.get(), 8);
+        }
+        assert_eq!(drops.get(), 10);
+    }
+
+    #[test]
+    fn inline_buffer_works() {
+        static EMPTY: CircularBuffer<i32, 4> = CircularBuffer::new();
+        assert!(EMPTY.is_empty());
+        assert_eq!(EMPTY.capacity(), 4);
+
+        let mut queue: CircularBuffer<i32, 3> = (1..=3).collect();
+        assert!(queue.is_full());
+        assert_eq!(queue.try_enqueue(4), Err(4));
+        assert_eq!(queue.dequeue(), Some(1));
+        queue.enqueue(4);
+        assert_eq!(queue.push_overwrite(5), Some(2));
+        assert_eq!(queue.collect(), vec![3, 4, 5]);
+        assert_eq!(format!("{}", queue), "[345]");
+        assert_eq!(format!("{:?}", queue), "[3, 4, 5]");
+        assert_eq!(queue.back(), Some(&5));
+
+        let copy: CircularBuffer<i32, 3> = [3, 4, 5].into_iter().collect();
+        assert_eq!(queue, copy);
+        assert_eq!(hash_of(&queue), hash_of(&copy));
+        assert_eq!(queue.clone(), copy);
+
+        let mut empty: CircularBuffer<i32, 0> = CircularBuffer::new();
+        assert_eq!(empty.push_overwrite(1), Some(1));
+    }
+
+    #[test]
+    #[should_panic(expected = "Buffer is full. Item not added")]
+    fn inline_buffer_collect_panics_past_capacity() {
+        let _: CircularBuffer<i32, 2> = (0..3).collect();
+    }
+
+    #[test]
+    fn zero_sized_types_work() {
+        let mut queue = HeapCircularBuffer::new(usize::MAX);
+        for _ in 0..5 {
+            queue.enqueue(());
+        }
+        queue.dequeue();
+        queue.enqueue(());
+        assert_eq!(queue.len(), 5);
+        assert_eq!(queue.iter().count(), 5);
+        assert_eq!(queue.back(), Some(&()));
+    }
 }
@@ -1,6 +1,8 @@
 use std::collections::LinkedList;
-use std::fmt::{self, Display};
+use std::fmt::{self, Debug, Display};
+use std::ops::Index;
 
+#[derive(Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
 pub struct LinkedListQueue<T: Copy + PartialEq> {
     buffer: LinkedList<T>,
 }
@@ -33,10 +35,43 @@ impl<T: Copy + PartialEq> LinkedListQueue<T> {
     }
 
     pub fn collect(&self) -> Vec<T> {
-        self.buffer
-            .iter()
-            .map(|elm| elm.clone())
-            .collect::<Vec<T>>()
+        self.buffer.iter().copied().collect::<Vec<T>>()
+    }
+}
+
+impl<T: Copy + PartialEq> Default for LinkedListQueue<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Copy + PartialEq> FromIterator<T> for LinkedListQueue<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut queue = Self::new();
+        queue.extend(iter);
+        queue
+    }
+}
+
+impl<T: Copy + PartialEq> Extend<T> for LinkedListQueue<T> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for elt in iter {
+            self.enqueue(elt);
+        }
+    }
+}
+
+impl<T: Copy + PartialEq> Index<usize> for LinkedListQueue<T> {
+    type Output = T;
+
+    fn index(&self, index: usize) -> &T {
+        self.buffer.iter().nth(index).expect("Index out of bounds")
+    }
+}
+
+impl<T: Copy + PartialEq + Debug> Debug for LinkedListQueue<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.buffer.iter()).finish()
     }
 }
 
@@ -60,6 +95,7 @@ impl<T: Copy + PartialEq + Display> Display for LinkedListQueue<T> {
 #[cfg(test)]
 mod tests {
     use super::LinkedListQueue;
+    use crate::queues::VecQueue;
 
     #[test]
     fn enqueue_works() {
@@ -111,4 +147,23 @@ mod tests {
 
         assert_eq!(queue.len(), 0);
     }
+
+    #[test]
+    fn agrees_with_vec_queue() {
+        let mut queue: LinkedListQueue<i32> = (0..4).collect();
+        let mut vec_queue: VecQueue<i32> = (0..4).collect();
+        queue.dequeue();
+        vec_queue.dequeue();
+        queue.enqueue(4);
+        vec_queue.enqueue(4);
+
+        assert_eq!(queue.top(), vec_queue.top());
+        assert_eq!(queue[0], vec_queue[0]);
+        assert_eq!(queue[3], vec_queue[3]);
+        assert_eq!(format!("{:?}", queue), format!("{:?}", vec_queue));
+
+        let prefix: LinkedListQueue<i32> = (1..4).collect();
+        assert!(prefix < queue);
+        assert_eq!(queue.clone(), queue);
+    }
 }
@@ -1,7 +1,12 @@
-mod circular_buffer;
-mod linked_list_queue;
-mod vec_queue;
+mod cache_padded;
+pub mod circular_buffer;
+pub mod linked_list_queue;
+pub mod mpmc_queue;
+pub mod spsc_ring;
+pub mod vec_queue;
 
-pub use self::circular_buffer::CircularBuffer;
+pub use self::circular_buffer::{CircularBuffer, HeapCircularBuffer, RingBuffer};
 pub use self::linked_list_queue::LinkedListQueue;
+pub use self::mpmc_queue::MpmcQueue;
+pub use self::spsc_ring::SpscRing;
 pub use self::vec_queue::VecQueue;
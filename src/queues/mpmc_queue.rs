@@ -0,0 +1,426 @@
+use super::cache_padded::CachePadded;
+use std::cell::UnsafeCell;
+use std::fmt::{self, Debug};
+use std::hint;
+use std::mem::MaybeUninit;
+use std::sync::atomic::{self, AtomicUsize, Ordering};
+use std::sync::{Condvar, Mutex};
+use std::thread;
+
+struct Slot<T> {
+    // `position` when the slot is free for the push claiming that position,
+    // `position + 1` once that push has written it. Popping moves it on to
+    // the same index one lap later.
+    stamp: AtomicUsize,
+    value: UnsafeCell<MaybeUninit<T>>,
+}
+
+// Threads blocked on one side of the queue. The count lets the other side
+// skip the lock entirely when nobody is waiting.
+struct Waiters {
+    count: AtomicUsize,
+    lock: Mutex<()>,
+    cond: Condvar,
+}
+
+impl Waiters {
+    fn new() -> Self {
+        Waiters {
+            count: AtomicUsize::new(0),
+            lock: Mutex::new(()),
+            cond: Condvar::new(),
+        }
+    }
+
+    // Blocks until `attempt` succeeds. The last attempt is made under the
+    // lock after registering as a waiter, so a `notify` racing with it
+    // either is seen by the attempt or wakes us up.
+    fn wait_for<R>(&self, mut attempt: impl FnMut() -> Option<R>) -> R {
+        if let Some(result) = attempt() {
+            return result;
+        }
+
+        let mut guard = self.lock.lock().unwrap_or_else(|e| e.into_inner());
+        self.count.fetch_add(1, Ordering::SeqCst);
+        loop {
+            atomic::fence(Ordering::SeqCst);
+            if let Some(result) = attempt() {
+                self.count.fetch_sub(1, Ordering::SeqCst);
+                return result;
+            }
+            guard = self.cond.wait(guard).unwrap_or_else(|e| e.into_inner());
+        }
+    }
+
+    fn notify(&self) {
+        atomic::fence(Ordering::SeqCst);
+        if self.count.load(Ordering::SeqCst) > 0 {
+            let _guard = self.lock.lock().unwrap_or_else(|e| e.into_inner());
+            self.cond.notify_one();
+        }
+    }
+}
+
+/// A bounded FIFO queue that any number of threads can push to and pop from
+/// concurrently, typically through an `Arc`.
+///
+/// This is Dmitry Vyukov's array queue: `try_push` and `try_pop` are
+/// lock-free, while `push` and `pop` park the calling thread on a condvar
+/// until there is room or an element to take.
+pub struct MpmcQueue<T> {
+    // Positions pack the slot index into the bits below `one_lap` and a lap
+    // counter into the bits above it, so a stale position never matches a
+    // slot's stamp from a different lap.
+    head: CachePadded<AtomicUsize>,
+    tail: CachePadded<AtomicUsize>,
+    one_lap: usize,
+    slots: Box<[Slot<T>]>,
+    not_empty: Waiters,
+    not_full: Waiters,
+}
+
+// Elements move between threads, but each one is only ever accessed by the
+// thread whose push or pop claimed its slot.
+unsafe impl<T: Send> Send for MpmcQueue<T> {}
+unsafe impl<T: Send> Sync for MpmcQueue<T> {}
+
+impl<T> MpmcQueue<T> {
+    /// Panics if `capacity` is zero.
+    pub fn new(capacity: usize) -> Self {
+        if capacity == 0 {
+            panic!("capacity must be non-zero");
+        }
+        let one_lap = capacity
+            .checked_add(1)
+            .and_then(usize::checked_next_power_of_two)
+            .expect("capacity overflow");
+
+        MpmcQueue {
+            head: CachePadded(AtomicUsize::new(0)),
+            tail: CachePadded(AtomicUsize::new(0)),
+            one_lap,
+            slots: (0..capacity)
+                .map(|i| Slot {
+                    stamp: AtomicUsize::new(i),
+                    value: UnsafeCell::new(MaybeUninit::uninit()),
+                })
+                .collect(),
+            not_empty: Waiters::new(),
+            not_full: Waiters::new(),
+        }
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.slots.len()
+    }
+
+    // The position after `pos`, moving on to the next lap past the last slot.
+    fn next(&self, pos: usize) -> usize {
+        let index = pos & (self.one_lap - 1);
+        if index + 1 < self.capacity() {
+            pos + 1
+        } else {
+            (pos & !(self.one_lap - 1)).wrapping_add(self.one_lap)
+        }
+    }
+
+    // `try_push` without waking blocked poppers. Waking them takes the other
+    // side's lock, which `push` mustn't do while it holds its own.
+    fn push_slot(&self, elt: T) -> Result<(), T> {
+        let mut tail = self.tail.load(Ordering::Relaxed);
+
+        loop {
+            let slot = &self.slots[tail & (self.one_lap - 1)];
+            let stamp = slot.stamp.load(Ordering::Acquire);
+
+            if stamp == tail {
+                // The slot is free for this lap; try to claim it.
+                match self.tail.compare_exchange_weak(
+                    tail,
+                    self.next(tail),
+                    Ordering::SeqCst,
+                    Ordering::Relaxed,
+                ) {
+                    Ok(_) => {
+                        unsafe { (*slot.value.get()).write(elt) };
+                        slot.stamp.store(tail + 1, Ordering::Release);
+                        return Ok(());
+                    }
+                    Err(current) => {
+                        tail = current;
+                        hint::spin_loop();
+                    }
+                }
+            } else if stamp.wrapping_add(self.one_lap) == tail + 1 {
+                // The slot still holds last lap's element. Unless a pop has
+                // claimed it and is reading it out, the queue is full.
+                atomic::fence(Ordering::SeqCst);
+                let head = self.head.load(Ordering::Relaxed);
+                if head.wrapping_add(self.one_lap) == tail {
+                    return Err(elt);
+                }
+                hint::spin_loop();
+                tail = self.tail.load(Ordering::Relaxed);
+            } else {
+                // Our `tail` is stale, or another thread is partway through
+                // this slot; wait for the stamp to move on.
+                thread::yield_now();
+                tail = self.tail.load(Ordering::Relaxed);
+            }
+        }
+    }
+
+    // `try_pop` without waking blocked pushers, for the same reason.
+    fn pop_slot(&self) -> Option<T> {
+        let mut head = self.head.load(Ordering::Relaxed);
+
+        loop {
+            let slot = &self.slots[head & (self.one_lap - 1)];
+            let stamp = slot.stamp.load(Ordering::Acquire);
+
+            if stamp == head + 1 {
+                // The slot holds this lap's element; try to claim it.
+                match self.head.compare_exchange_weak(
+                    head,
+                    self.next(head),
+                    Ordering::SeqCst,
+                    Ordering::Relaxed,
+                ) {
+                    Ok(_) => {
+                        let elt = unsafe { (*slot.value.get()).assume_init_read() };
+                        slot.stamp
+                            .store(head.wrapping_add(self.one_lap), Ordering::Release);
+                        return Some(elt);
+                    }
+                    Err(current) => {
+                        head = current;
+                        hint::spin_loop();
+                    }
+                }
+            } else if stamp == head {
+                // Nothing has been written here yet this lap. Unless a push
+                // has claimed the slot and is writing it, the queue is empty.
+                atomic::fence(Ordering::SeqCst);
+                let tail = self.tail.load(Ordering::Relaxed);
+                if tail == head {
+                    return None;
+                }
+                hint::spin_loop();
+                head = self.head.load(Ordering::Relaxed);
+            } else {
+                // Our `head` is stale, or another thread is partway through
+                // this slot; wait for the stamp to move on.
+                thread::yield_now();
+                head = self.head.load(Ordering::Relaxed);
+            }
+        }
+    }
+
+    /// Hands the element back if the queue is full.
+    pub fn try_push(&self, elt: T) -> Result<(), T> {
+        self.push_slot(elt)?;
+        self.not_empty.notify();
+        Ok(())
+    }
+
+    pub fn try_pop(&self) -> Option<T> {
+        let elt = self.pop_slot()?;
+        self.not_full.notify();
+        Some(elt)
+    }
+
+    /// Blocks until there is room for the element.
+    pub fn push(&self, elt: T) {
+        let mut elt = Some(elt);
+        self.not_full
+            .wait_for(|| match self.push_slot(elt.take()?) {
+                Ok(()) => Some(()),
+                Err(rejected) => {
+                    elt = Some(rejected);
+                    None
+                }
+            });
+        self.not_empty.notify();
+    }
+
+    /// Blocks until there is an element to take.
+    pub fn pop(&self) -> T {
+        let elt = self.not_empty.wait_for(|| self.pop_slot());
+        self.not_full.notify();
+        elt
+    }
+
+    /// A snapshot of the number of queued elements, which other threads may
+    /// change at any time.
+    pub fn len(&self) -> usize {
+        loop {
+            let tail = self.tail.load(Ordering::SeqCst);
+            let head = self.head.load(Ordering::SeqCst);
+
+            // Retry unless both positions were read at the same instant.
+            if self.tail.load(Ordering::SeqCst) == tail {
+                let head_index = head & (self.one_lap - 1);
+                let tail_index = tail & (self.one_lap - 1);
+
+                return if head_index < tail_index {
+                    tail_index - head_index
+                } else if head_index > tail_index {
+                    self.capacity() - head_index + tail_index
+                } else if tail == head {
+                    0
+                } else {
+                    self.capacity()
+                };
+            }
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn is_full(&self) -> bool {
+        self.len() == self.capacity()
+    }
+}
+
+impl<T> Drop for MpmcQueue<T> {
+    fn drop(&mut self) {
+        let head_index = *self.head.0.get_mut() & (self.one_lap - 1);
+
+        for i in 0..self.len() {
+            let index = if head_index + i < self.capacity() {
+                head_index + i
+            } else {
+                head_index + i - self.capacity()
+            };
+            unsafe { self.slots[index].value.get_mut().assume_init_drop() };
+        }
+    }
+}
+
+impl<T> Debug for MpmcQueue<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("MpmcQueue")
+            .field("capacity", &self.capacity())
+            .field("len", &self.len())
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::MpmcQueue;
+    use crate::test_util::DropCounter;
+    use std::cell::Cell;
+    use std::sync::Arc;
+    use std::thread;
+
+    #[test]
+    fn try_push_and_try_pop_work() {
+        let queue = MpmcQueue::new(3);
+        assert_eq!(queue.try_pop(), None);
+        assert_eq!(queue.try_push(1), Ok(()));
+        assert_eq!(queue.try_push(2), Ok(()));
+        assert_eq!(queue.try_push(3), Ok(()));
+        assert_eq!(queue.try_push(4), Err(4));
+        assert!(queue.is_full());
+        assert_eq!(queue.len(), 3);
+
+        assert_eq!(queue.try_pop(), Some(1));
+        assert_eq!(queue.try_push(4), Ok(()));
+
+        // Go round the queue a few times.
+        for i in 5..20 {
+            assert_eq!(queue.try_pop(), Some(i - 3));
+            assert_eq!(queue.try_push(i), Ok(()));
+            assert_eq!(queue.len(), 3);
+        }
+        assert_eq!(queue.pop(), 17);
+        assert_eq!(queue.pop(), 18);
+        assert_eq!(queue.pop(), 19);
+        assert_eq!(queue.try_pop(), None);
+        assert!(queue.is_empty());
+    }
+
+    #[test]
+    fn drops_each_element_once() {
+        let drops = Cell::new(0);
+        {
+            let queue = MpmcQueue::new(3);
+            for _ in 0..3 {
+                queue.push(DropCounter::new(&drops));
+            }
+            drop(queue.try_push(DropCounter::new(&drops)));
+            assert_eq!(drops.get(), 1);
+
+            drop(queue.pop());
+            drop(queue.pop());
+            queue.push(DropCounter::new(&drops));
+            queue.push(DropCounter::new(&drops));
+            assert_eq!(drops.get(), 3);
+        }
+        assert_eq!(drops.get(), 6);
+    }
+
+    #[test]
+    #[should_panic(expected = "capacity must be non-zero")]
+    fn new_panics_on_zero_capacity() {
+        let _ = MpmcQueue::<i32>::new(0);
+    }
+
+    #[test]
+    fn transfers_between_threads() {
+        const THREADS: usize = 4;
+        let count = if cfg!(miri) { 50 } else { 10_000 };
+        let queue = Arc::new(MpmcQueue::new(8));
+
+        let producers: Vec<_> = (0..THREADS)
+            .map(|t| {
+                let queue = Arc::clone(&queue);
+                thread::spawn(move || {
+                    for i in 0..count {
+                        // Mix blocking and non-blocking pushes.
+                        let mut value = t * count + i;
+                        if i % 2 == 0 {
+                            while let Err(rejected) = queue.try_push(value) {
+                                value = rejected;
+                                thread::yield_now();
+                            }
+                        } else {
+                            queue.push(value);
+                        }
+                    }
+                })
+            })
+            .collect();
+
+        let consumers: Vec<_> = (0..THREADS)
+            .map(|_| {
+                let queue = Arc::clone(&queue);
+                thread::spawn(move || (0..count).map(|_| queue.pop()).collect::<Vec<_>>())
+            })
+            .collect();
+
+        for producer in producers {
+            producer.join().unwrap();
+        }
+
+        let mut seen = vec![false; THREADS * count];
+        for consumer in consumers {
+            let values = consumer.join().unwrap();
+
+            // Each producer's values reach a given consumer in push order.
+            for t in 0..THREADS {
+                let from_t = values.iter().filter(|&&v| v / count == t);
+                assert!(from_t.clone().zip(from_t.skip(1)).all(|(a, b)| a < b));
+            }
+            for value in values {
+                assert!(!seen[value]);
+                seen[value] = true;
+            }
+        }
+        assert!(seen.iter().all(|&s| s));
+        assert!(queue.is_empty());
+    }
+}
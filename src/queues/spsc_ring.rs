@@ -0,0 +1,447 @@
+use super::cache_padded::CachePadded;
+use std::cell::UnsafeCell;
+use std::fmt::{self, Debug};
+use std::mem::MaybeUninit;
+use std::ptr;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+/// A bounded, lock-free ring buffer for exactly one producer thread and one
+/// consumer thread.
+///
+/// `split` hands out a `Producer` and a `Consumer`, each of which can be
+/// moved to its own thread. Neither side ever blocks: `push` hands the
+/// element back when the ring is full and `pop` returns `None` when it is
+/// empty.
+pub struct SpscRing<T> {
+    // Positions run over `0..2 * capacity` so that a full ring and an empty
+    // one can be told apart without sacrificing a slot.
+    head: CachePadded<AtomicUsize>,
+    tail: CachePadded<AtomicUsize>,
+    slots: Box<[UnsafeCell<MaybeUninit<T>>]>,
+}
+
+// Elements only ever move from the producer's thread to the consumer's, and
+// each slot is accessed by one side at a time.
+unsafe impl<T: Send> Send for SpscRing<T> {}
+unsafe impl<T: Send> Sync for SpscRing<T> {}
+
+impl<T> SpscRing<T> {
+    pub fn new(capacity: usize) -> Self {
+        if capacity > usize::MAX / 2 {
+            panic!("capacity overflow");
+        }
+
+        SpscRing {
+            head: CachePadded(AtomicUsize::new(0)),
+            tail: CachePadded(AtomicUsize::new(0)),
+            slots: (0..capacity)
+                .map(|_| UnsafeCell::new(MaybeUninit::uninit()))
+                .collect(),
+        }
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.slots.len()
+    }
+
+    pub fn split(self) -> (Producer<T>, Consumer<T>) {
+        let ring = Arc::new(self);
+        let producer = Producer {
+            ring: Arc::clone(&ring),
+            head: 0,
+            tail: 0,
+        };
+        let consumer = Consumer {
+            ring,
+            head: 0,
+            tail: 0,
+        };
+        (producer, consumer)
+    }
+
+    fn slot(&self, index: usize) -> *mut T {
+        UnsafeCell::raw_get(self.slots.as_ptr().wrapping_add(index)).cast()
+    }
+
+    fn index(&self, pos: usize) -> usize {
+        if pos >= self.capacity() {
+            pos - self.capacity()
+        } else {
+            pos
+        }
+    }
+
+    fn advance(&self, pos: usize, count: usize) -> usize {
+        let to_end = 2 * self.capacity() - pos;
+        if count >= to_end {
+            count - to_end
+        } else {
+            pos + count
+        }
+    }
+
+    fn distance(&self, from: usize, to: usize) -> usize {
+        if to >= from {
+            to - from
+        } else {
+            to + (2 * self.capacity() - from)
+        }
+    }
+
+    // Splits `count` slots starting at `pos` into the run up to the end of
+    // the buffer and the run that wraps around to the start.
+    fn runs(&self, pos: usize, count: usize) -> (usize, usize) {
+        let to_end = self.capacity() - self.index(pos);
+        if count > to_end {
+            (to_end, count - to_end)
+        } else {
+            (count, 0)
+        }
+    }
+}
+
+impl<T> Drop for SpscRing<T> {
+    fn drop(&mut self) {
+        let mut head = self.head.load(Ordering::Relaxed);
+        let tail = self.tail.load(Ordering::Relaxed);
+
+        while head != tail {
+            unsafe { ptr::drop_in_place(self.slot(self.index(head))) };
+            head = self.advance(head, 1);
+        }
+    }
+}
+
+impl<T> Debug for SpscRing<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SpscRing")
+            .field("capacity", &self.capacity())
+            .finish_non_exhaustive()
+    }
+}
+
+/// The writing half of an `SpscRing`.
+pub struct Producer<T> {
+    ring: Arc<SpscRing<T>>,
+    // The consumer's position as of the last time we looked. It only ever
+    // lags behind, so the free space it implies is a safe underestimate.
+    head: usize,
+    tail: usize,
+}
+
+impl<T> Producer<T> {
+    // Returns the number of free slots, only reloading the consumer's
+    // position if the cached one doesn't leave room for `wanted`.
+    fn free(&mut self, wanted: usize) -> usize {
+        let free = self.ring.capacity() - self.ring.distance(self.head, self.tail);
+        if free >= wanted {
+            return free;
+        }
+
+        self.head = self.ring.head.load(Ordering::Acquire);
+        self.ring.capacity() - self.ring.distance(self.head, self.tail)
+    }
+
+    /// Hands the element back if the ring is full.
+    pub fn push(&mut self, elt: T) -> Result<(), T> {
+        if self.free(1) == 0 {
+            return Err(elt);
+        }
+
+        unsafe { self.ring.slot(self.ring.index(self.tail)).write(elt) };
+        self.tail = self.ring.advance(self.tail, 1);
+        self.ring.tail.store(self.tail, Ordering::Release);
+        Ok(())
+    }
+
+    /// Pushes as many elements from the front of `elts` as fit, publishing
+    /// them to the consumer all at once. Returns how many were pushed.
+    pub fn push_slice(&mut self, elts: &[T]) -> usize
+    where
+        T: Copy,
+    {
+        let count = self.free(elts.len()).min(elts.len());
+        let (first, second) = self.ring.runs(self.tail, count);
+
+        unsafe {
+            let src = elts.as_ptr();
+            ptr::copy_nonoverlapping(src, self.ring.slot(self.ring.index(self.tail)), first);
+            ptr::copy_nonoverlapping(src.add(first), self.ring.slot(0), second);
+        }
+        self.tail = self.ring.advance(self.tail, count);
+        self.ring.tail.store(self.tail, Ordering::Release);
+        count
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.ring.capacity()
+    }
+
+    /// The number of elements waiting to be popped. The consumer may take
+    /// more at any time, so this can be an overestimate.
+    pub fn len(&self) -> usize {
+        let head = self.ring.head.load(Ordering::Acquire);
+        self.ring.distance(head, self.tail)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn is_full(&self) -> bool {
+        self.len() == self.capacity()
+    }
+
+    /// Whether the consumer has been dropped.
+    pub fn is_abandoned(&self) -> bool {
+        Arc::strong_count(&self.ring) == 1
+    }
+}
+
+impl<T> Debug for Producer<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Producer")
+            .field("capacity", &self.capacity())
+            .finish_non_exhaustive()
+    }
+}
+
+/// The reading half of an `SpscRing`.
+pub struct Consumer<T> {
+    ring: Arc<SpscRing<T>>,
+    head: usize,
+    // The producer's position as of the last time we looked. It only ever
+    // lags behind, so the element count it implies is a safe underestimate.
+    tail: usize,
+}
+
+impl<T> Consumer<T> {
+    // Returns the number of queued elements, only reloading the producer's
+    // position if the cached one doesn't cover `wanted`.
+    fn queued(&mut self, wanted: usize) -> usize {
+        let queued = self.ring.distance(self.head, self.tail);
+        if queued >= wanted {
+            return queued;
+        }
+
+        self.tail = self.ring.tail.load(Ordering::Acquire);
+        self.ring.distance(self.head, self.tail)
+    }
+
+    pub fn pop(&mut self) -> Option<T> {
+        if self.queued(1) == 0 {
+            return None;
+        }
+
+        let elt = unsafe { self.ring.slot(self.ring.index(self.head)).read() };
+        self.head = self.ring.advance(self.head, 1);
+        self.ring.head.store(self.head, Ordering::Release);
+        Some(elt)
+    }
+
+    /// Pops into the front of `buf` as many elements as are queued and fit,
+    /// releasing their slots to the producer all at once. Returns how many
+    /// were popped.
+    pub fn pop_slice(&mut self, buf: &mut [T]) -> usize
+    where
+        T: Copy,
+    {
+        let count = self.queued(buf.len()).min(buf.len());
+        let (first, second) = self.ring.runs(self.head, count);
+
+        unsafe {
+            let dst = buf.as_mut_ptr();
+            ptr::copy_nonoverlapping(self.ring.slot(self.ring.index(self.head)), dst, first);
+            ptr::copy_nonoverlapping(self.ring.slot(0), dst.add(first), second);
+        }
+        self.head = self.ring.advance(self.head, count);
+        self.ring.head.store(self.head, Ordering::Release);
+        count
+    }
+
+    /// The element `pop` would return next.
+    ///
+    /// Takes `&mut self` because a `Consumer` is `Sync` even when `T` isn't,
+    /// so a shared one must not hand the same element to several threads:
+    ///
+    /// ```compile_fail
+    /// use dust::queues::SpscRing;
+    /// use std::cell::Cell;
+    ///
+    /// let (mut producer, consumer) = SpscRing::new(1).split();
+    /// producer.push(Cell::new(1)).unwrap();
+    /// let consumer = &consumer;
+    /// std::thread::scope(|s| {
+    ///     s.spawn(move || consumer.peek().map(|cell| cell.set(2)));
+    ///     s.spawn(move || consumer.peek().map(|cell| cell.set(3)));
+    /// });
+    /// ```
+    pub fn peek(&mut self) -> Option<&T> {
+        if self.queued(1) == 0 {
+            return None;
+        }
+        unsafe { Some(&*self.ring.slot(self.ring.index(self.head))) }
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.ring.capacity()
+    }
+
+    /// The number of elements waiting to be popped. The producer may add
+    /// more at any time, so this can be an underestimate.
+    pub fn len(&self) -> usize {
+        let tail = self.ring.tail.load(Ordering::Acquire);
+        self.ring.distance(self.head, tail)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Whether the producer has been dropped. Once it has, whatever is left
+    /// in the ring is all that will ever arrive.
+    pub fn is_abandoned(&self) -> bool {
+        Arc::strong_count(&self.ring) == 1
+    }
+}
+
+impl<T> Debug for Consumer<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Consumer")
+            .field("capacity", &self.capacity())
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::SpscRing;
+    use crate::test_util::DropCounter;
+    use std::cell::Cell;
+    use std::thread;
+
+    #[test]
+    fn push_and_pop_work() {
+        let (mut producer, mut consumer) = SpscRing::new(3).split();
+        assert_eq!(consumer.pop(), None);
+        assert_eq!(producer.push(1), Ok(()));
+        assert_eq!(producer.push(2), Ok(()));
+        assert_eq!(producer.push(3), Ok(()));
+        assert_eq!(producer.push(4), Err(4));
+        assert!(producer.is_full());
+        assert_eq!(consumer.len(), 3);
+
+        assert_eq!(consumer.peek(), Some(&1));
+        assert_eq!(consumer.pop(), Some(1));
+        assert_eq!(producer.push(4), Ok(()));
+
+        // Go round the ring a few times.
+        for i in 5..20 {
+            assert_eq!(consumer.pop(), Some(i - 3));
+            assert_eq!(producer.push(i), Ok(()));
+        }
+        assert_eq!(consumer.pop(), Some(17));
+        assert_eq!(consumer.pop(), Some(18));
+        assert_eq!(consumer.pop(), Some(19));
+        assert_eq!(consumer.pop(), None);
+        assert!(producer.is_empty());
+    }
+
+    #[test]
+    fn slices_wrap_around() {
+        let (mut producer, mut consumer) = SpscRing::new(4).split();
+        let mut buf = [0; 8];
+
+        assert_eq!(producer.push_slice(&[1, 2, 3]), 3);
+        assert_eq!(consumer.pop_slice(&mut buf[..2]), 2);
+        assert_eq!(buf[..2], [1, 2]);
+
+        assert_eq!(producer.push_slice(&[4, 5, 6, 7, 8]), 3);
+        assert_eq!(producer.push_slice(&[8]), 0);
+        assert_eq!(consumer.pop_slice(&mut buf), 4);
+        assert_eq!(buf[..4], [3, 4, 5, 6]);
+        assert_eq!(consumer.pop_slice(&mut buf), 0);
+
+        assert_eq!(producer.push_slice(&[]), 0);
+        assert!(consumer.is_empty());
+    }
+
+    #[test]
+    fn drops_each_element_once() {
+        let drops = Cell::new(0);
+        {
+            let (mut producer, mut consumer) = SpscRing::new(3).split();
+            for _ in 0..3 {
+                assert!(producer.push(DropCounter::new(&drops)).is_ok());
+            }
+            drop(producer.push(DropCounter::new(&drops)));
+            assert_eq!(drops.get(), 1);
+
+            drop(consumer.pop());
+            assert!(producer.push(DropCounter::new(&drops)).is_ok());
+            assert_eq!(drops.get(), 2);
+
+            drop(producer);
+            assert!(consumer.is_abandoned());
+        }
+        assert_eq!(drops.get(), 5);
+    }
+
+    #[test]
+    fn zero_capacity_works() {
+        let (mut producer, mut consumer) = SpscRing::new(0).split();
+        assert_eq!(producer.push(1), Err(1));
+        assert_eq!(producer.push_slice(&[1]), 0);
+        assert_eq!(consumer.pop(), None);
+        assert!(producer.is_full());
+        assert!(consumer.is_empty());
+    }
+
+    #[test]
+    #[should_panic(expected = "capacity overflow")]
+    fn new_panics_on_huge_capacity() {
+        let _ = SpscRing::<()>::new(usize::MAX);
+    }
+
+    #[test]
+    fn transfers_between_threads() {
+        let count = if cfg!(miri) { 200 } else { 100_000 };
+        let (mut producer, mut consumer) = SpscRing::new(16).split();
+
+        let sender = thread::spawn(move || {
+            let mut next = 0;
+            while next < count {
+                // Alternate between single and batched pushes.
+                if next % 2 == 0 {
+                    if producer.push(next).is_ok() {
+                        next += 1;
+                    }
+                } else {
+                    let batch: Vec<usize> = (next..count.min(next + 5)).collect();
+                    next += producer.push_slice(&batch);
+                }
+                thread::yield_now();
+            }
+        });
+
+        let mut expected = 0;
+        let mut buf = [0; 7];
+        while expected < count {
+            let popped = consumer.pop_slice(&mut buf);
+            for &value in &buf[..popped] {
+                assert_eq!(value, expected);
+                expected += 1;
+            }
+            if let Some(value) = consumer.pop() {
+                assert_eq!(value, expected);
+                expected += 1;
+            }
+            thread::yield_now();
+        }
+
+        sender.join().unwrap();
+        assert!(consumer.is_abandoned());
+        assert_eq!(consumer.pop(), None);
+    }
+}
@@ -1,12 +1,35 @@
-use std::fmt::{self, Display};
+use std::cmp::Ordering;
+use std::fmt::{self, Debug, Display};
+use std::hash::{Hash, Hasher};
+use std::ops::Index;
+use std::slice;
+use std::vec;
 
+/// A FIFO queue over a `Vec`.
+///
+/// Dequeuing only advances `head`; the dead prefix is compacted away once it
+/// outgrows the live elements, so both ends are amortized O(1).
+#[derive(Clone)]
 pub struct VecQueue<T: Copy + PartialEq> {
     buffer: Vec<T>,
+    head: usize,
 }
 
 impl<T: Copy + PartialEq> VecQueue<T> {
     pub fn new() -> Self {
-        VecQueue { buffer: Vec::new() }
+        VecQueue {
+            buffer: Vec::new(),
+            head: 0,
+        }
+    }
+
+    fn as_slice(&self) -> &[T] {
+        &self.buffer[self.head..]
+    }
+
+    fn compact(&mut self) {
+        self.buffer.drain(..self.head);
+        self.head = 0;
     }
 
     pub fn enqueue(&mut self, elt: T) {
@@ -14,44 +37,121 @@ impl<T: Copy + PartialEq> VecQueue<T> {
     }
 
     pub fn dequeue(&mut self) -> Option<T> {
-        if self.buffer.len() == 0 {
-            return None;
-        }
+        let result = *self.buffer.get(self.head)?;
+        self.head += 1;
 
-        let buffer = self.buffer.as_slice();
-        let result = buffer[0];
-        self.buffer = buffer[1..].to_vec();
+        // Each compaction moves fewer elements than were dequeued since the
+        // last one, which keeps the copying amortized O(1).
+        if self.head == self.buffer.len() {
+            self.buffer.clear();
+            self.head = 0;
+        } else if self.head > self.buffer.len() - self.head {
+            self.compact();
+        }
         Some(result)
     }
 
     pub fn top(&self) -> Option<&T> {
-        if self.buffer.len() == 0 {
-            return None;
-        }
-
-        Some(&self.buffer[0])
+        self.as_slice().first()
     }
 
     pub fn len(&self) -> usize {
-        self.buffer.len()
+        self.buffer.len() - self.head
     }
 
     pub fn is_empty(&self) -> bool {
-        self.buffer.is_empty()
+        self.len() == 0
+    }
+
+    pub fn iter(&self) -> slice::Iter<'_, T> {
+        self.as_slice().iter()
+    }
+
+    /// Removes every element, yielding them in queue order.
+    pub fn drain(&mut self) -> vec::Drain<'_, T> {
+        self.compact();
+        self.buffer.drain(..)
     }
 
     pub fn collect(&self) -> Vec<T> {
-        self.buffer
-            .iter()
-            .map(|elm| elm.clone())
-            .collect::<Vec<T>>()
+        self.as_slice().to_vec()
+    }
+}
+
+impl<T: Copy + PartialEq> Default for VecQueue<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Copy + PartialEq> FromIterator<T> for VecQueue<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut queue = Self::new();
+        queue.extend(iter);
+        queue
+    }
+}
+
+impl<T: Copy + PartialEq> Extend<T> for VecQueue<T> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        self.buffer.extend(iter)
+    }
+}
+
+impl<'a, T: Copy + PartialEq> IntoIterator for &'a VecQueue<T> {
+    type Item = &'a T;
+    type IntoIter = slice::Iter<'a, T>;
+
+    fn into_iter(self) -> slice::Iter<'a, T> {
+        self.iter()
+    }
+}
+
+impl<T: Copy + PartialEq> Index<usize> for VecQueue<T> {
+    type Output = T;
+
+    fn index(&self, index: usize) -> &T {
+        &self.as_slice()[index]
+    }
+}
+
+impl<T: Copy + PartialEq + Debug> Debug for VecQueue<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+// The comparisons only look at the queued elements, not the dead prefix.
+impl<T: Copy + PartialEq> PartialEq for VecQueue<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.as_slice() == other.as_slice()
+    }
+}
+
+impl<T: Copy + Eq> Eq for VecQueue<T> {}
+
+impl<T: Copy + PartialEq + Hash> Hash for VecQueue<T> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.as_slice().hash(state)
+    }
+}
+
+impl<T: Copy + PartialOrd> PartialOrd for VecQueue<T> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        self.as_slice().partial_cmp(other.as_slice())
+    }
+}
+
+impl<T: Copy + Ord> Ord for VecQueue<T> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.as_slice().cmp(other.as_slice())
     }
 }
 
 impl<T: Copy + PartialEq + Display> Display for VecQueue<T> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(f, "[")?;
-        let mut iter = self.buffer.iter();
+        let mut iter = self.iter();
 
         if let Some(value) = iter.next() {
             write!(f, "{}", value)?;
@@ -68,6 +168,7 @@ impl<T: Copy + PartialEq + Display> Display for VecQueue<T> {
 #[cfg(test)]
 mod tests {
     use super::VecQueue;
+    use crate::test_util::hash_of;
 
     #[test]
     fn enqueue_works() {
@@ -119,4 +220,62 @@ mod tests {
 
         assert_eq!(queue.len(), 0);
     }
+
+    #[test]
+    fn comparisons_ignore_the_dead_prefix() {
+        let mut queue: VecQueue<i32> = (0..4).collect();
+        queue.dequeue();
+        let fresh: VecQueue<i32> = (1..4).collect();
+        assert_eq!(queue, fresh);
+        assert_eq!(hash_of(&queue), hash_of(&fresh));
+        assert_eq!(queue.clone(), fresh);
+
+        // The dead 0 would sort the queue before [0, 9] if it were compared.
+        let other: VecQueue<i32> = [0, 9].into_iter().collect();
+        assert!(queue > other);
+    }
+
+    #[test]
+    fn dequeue_reuses_the_buffer() {
+        let mut queue = VecQueue::new();
+        queue.extend([0, 1, 2]);
+        for i in 3..10_000 {
+            queue.enqueue(i);
+            assert_eq!(queue.dequeue(), Some(i - 3));
+            assert_eq!(queue.len(), 3);
+        }
+
+        assert_eq!(queue.collect(), vec![9997, 9998, 9999]);
+        assert!(queue.buffer.capacity() <= 8);
+    }
+
+    #[test]
+    fn dead_prefix_is_ignored() {
+        let mut queue: VecQueue<i32> = (0..10).collect();
+        for _ in 0..4 {
+            queue.dequeue();
+        }
+        assert_eq!(queue.head, 4);
+        assert_eq!(queue[0], 4);
+        assert_eq!(queue.top(), Some(&4));
+        assert_eq!(format!("{:?}", queue), "[4, 5, 6, 7, 8, 9]");
+        assert_eq!(queue, (4..10).collect());
+        assert!(queue.iter().copied().eq(4..10));
+        assert!((&queue).into_iter().rev().copied().eq((4..10).rev()));
+    }
+
+    #[test]
+    fn drain_works() {
+        let mut queue: VecQueue<i32> = (0..6).collect();
+        queue.dequeue();
+        queue.dequeue();
+
+        assert!(queue.drain().eq(2..6));
+        assert!(queue.is_empty());
+        assert_eq!(queue.dequeue(), None);
+
+        queue.extend([7, 8]);
+        assert_eq!(queue.drain().next(), Some(7));
+        assert!(queue.is_empty());
+    }
 }
@@ -0,0 +1,172 @@
+use crate::sets::set_ops::{fmt_set, impl_set_operators, Set};
+use crate::trees::b_tree::{self, DEFAULT_BRANCH_FACTOR};
+use crate::trees::BTree;
+use std::fmt::{self, Debug, Display};
+
+pub struct BTreeSet<T> {
+    tree: BTree<T>,
+    branch_factor: usize,
+}
+
+impl<T> BTreeSet<T>
+where
+    T: Ord + Copy + Debug + Default,
+{
+    pub fn new() -> Self {
+        Self::with_branch_factor(DEFAULT_BRANCH_FACTOR)
+    }
+
+    pub fn with_branch_factor(branch_factor: usize) -> Self {
+        BTreeSet {
+            tree: BTree::new(branch_factor),
+            branch_factor,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.tree.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.tree.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.tree = BTree::new(self.branch_factor);
+    }
+
+    /// Iterates in ascending order.
+    pub fn iter(&self) -> b_tree::Iter<'_, T> {
+        self.tree.iter()
+    }
+
+    /// Adds a value, returning false if it was already present.
+    pub fn insert(&mut self, value: T) -> bool {
+        if self.tree.search(value) {
+            return false;
+        }
+
+        self.tree.insert(value);
+        true
+    }
+
+    pub fn contains(&self, value: &T) -> bool {
+        self.tree.search(*value)
+    }
+
+    pub fn remove(&mut self, value: &T) -> bool {
+        self.tree.remove(*value)
+    }
+}
+
+impl<T> Set<T> for BTreeSet<T>
+where
+    T: Ord + Copy + Debug + Default,
+{
+    type Iter<'a>
+        = b_tree::Iter<'a, T>
+    where
+        Self: 'a,
+        T: 'a;
+
+    fn len(&self) -> usize {
+        self.tree.len()
+    }
+
+    fn iter(&self) -> b_tree::Iter<'_, T> {
+        self.tree.iter()
+    }
+
+    fn contains(&self, value: &T) -> bool {
+        self.tree.search(*value)
+    }
+}
+
+impl<T> Default for BTreeSet<T>
+where
+    T: Ord + Copy + Debug + Default,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> FromIterator<T> for BTreeSet<T>
+where
+    T: Ord + Copy + Debug + Default,
+{
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut set = BTreeSet::new();
+        set.extend(iter);
+        set
+    }
+}
+
+impl<T> Extend<T> for BTreeSet<T>
+where
+    T: Ord + Copy + Debug + Default,
+{
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for value in iter {
+            self.insert(value);
+        }
+    }
+}
+
+impl<'a, T> IntoIterator for &'a BTreeSet<T>
+where
+    T: Ord + Copy + Debug + Default,
+{
+    type Item = &'a T;
+    type IntoIter = b_tree::Iter<'a, T>;
+
+    fn into_iter(self) -> b_tree::Iter<'a, T> {
+        self.iter()
+    }
+}
+
+impl_set_operators!(BTreeSet<T> where T: Ord + Copy + Debug + Default);
+
+impl<T> Display for BTreeSet<T>
+where
+    T: Ord + Copy + Debug + Default + Display,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt_set(f, self.iter())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::BTreeSet;
+
+    fn values<'a>(iter: impl Iterator<Item = &'a i32>) -> Vec<i32> {
+        iter.copied().collect()
+    }
+
+    #[test]
+    fn insert_works() {
+        let mut set = BTreeSet::new();
+        assert!(set.insert(3));
+        assert!(set.insert(1));
+        assert!(set.insert(2));
+        assert!(!set.insert(3));
+        println!("set: {}", set);
+
+        assert_eq!(values(set.iter()), vec![1, 2, 3]);
+        assert_eq!(set.len(), 3);
+    }
+
+    #[test]
+    fn remove_works() {
+        let mut set: BTreeSet<i32> = (0..50).collect();
+
+        assert!(set.remove(&20));
+        assert!(!set.remove(&20));
+        assert!(!set.contains(&20));
+        assert_eq!(set.len(), 49);
+
+        set.clear();
+        assert!(set.is_empty());
+    }
+}
@@ -0,0 +1,200 @@
+use crate::maps::{chained_hash_map, ChainedHashMap};
+use crate::sets::set_ops::{fmt_set, impl_set_operators, Set};
+use std::borrow::Borrow;
+use std::collections::hash_map::RandomState;
+use std::fmt::{self, Display};
+use std::hash::{BuildHasher, Hash};
+
+pub struct HashSet<T, S = RandomState> {
+    map: ChainedHashMap<T, (), S>,
+}
+
+impl<T> HashSet<T, RandomState> {
+    pub fn new() -> Self {
+        Self::with_hasher(RandomState::new())
+    }
+
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self::with_capacity_and_hasher(capacity, RandomState::new())
+    }
+}
+
+impl<T, S> HashSet<T, S> {
+    pub fn with_hasher(hash_builder: S) -> Self {
+        HashSet {
+            map: ChainedHashMap::with_hasher(hash_builder),
+        }
+    }
+
+    pub fn with_capacity_and_hasher(capacity: usize, hash_builder: S) -> Self {
+        HashSet {
+            map: ChainedHashMap::with_capacity_and_hasher(capacity, hash_builder),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.map.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.map.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.map.clear()
+    }
+
+    pub fn iter(&self) -> Iter<'_, T> {
+        Iter {
+            iter: self.map.iter(),
+        }
+    }
+}
+
+impl<T, S> HashSet<T, S>
+where
+    T: Hash + Eq,
+    S: BuildHasher,
+{
+    /// Adds a value, returning false if it was already present.
+    pub fn insert(&mut self, value: T) -> bool {
+        if self.map.contains_key(&value) {
+            return false;
+        }
+
+        self.map.insert(value, ());
+        true
+    }
+
+    pub fn contains<Q>(&self, value: &Q) -> bool
+    where
+        T: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.map.contains_key(value)
+    }
+
+    pub fn remove<Q>(&mut self, value: &Q) -> bool
+    where
+        T: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.map.remove(value).is_some()
+    }
+}
+
+impl<T, S> Set<T> for HashSet<T, S>
+where
+    T: Hash + Eq,
+    S: BuildHasher,
+{
+    type Iter<'a>
+        = Iter<'a, T>
+    where
+        Self: 'a,
+        T: 'a;
+
+    fn len(&self) -> usize {
+        self.map.len()
+    }
+
+    fn iter(&self) -> Iter<'_, T> {
+        HashSet::iter(self)
+    }
+
+    fn contains(&self, value: &T) -> bool {
+        self.map.contains_key(value)
+    }
+}
+
+impl<T> Default for HashSet<T, RandomState> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T, S> FromIterator<T> for HashSet<T, S>
+where
+    T: Hash + Eq,
+    S: BuildHasher + Default,
+{
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut set = HashSet::with_hasher(S::default());
+        set.extend(iter);
+        set
+    }
+}
+
+impl<T, S> Extend<T> for HashSet<T, S>
+where
+    T: Hash + Eq,
+    S: BuildHasher,
+{
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for value in iter {
+            self.insert(value);
+        }
+    }
+}
+
+impl<'a, T, S> IntoIterator for &'a HashSet<T, S> {
+    type Item = &'a T;
+    type IntoIter = Iter<'a, T>;
+
+    fn into_iter(self) -> Iter<'a, T> {
+        self.iter()
+    }
+}
+
+impl_set_operators!(HashSet<T, S> where T: Hash + Eq + Clone, S: BuildHasher + Default);
+
+pub struct Iter<'a, T> {
+    iter: chained_hash_map::Iter<'a, T, ()>,
+}
+
+impl<'a, T> Iterator for Iter<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<&'a T> {
+        self.iter.next().map(|(value, _)| value)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
+impl<T> ExactSizeIterator for Iter<'_, T> {}
+
+impl<T: Display, S> Display for HashSet<T, S> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt_set(f, self.iter())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::HashSet;
+
+    #[test]
+    fn insert_works() {
+        let mut set = HashSet::new();
+        assert!(set.insert(1));
+        assert!(set.insert(2));
+        assert!(!set.insert(1));
+        println!("set: {}", set);
+
+        assert!(set.contains(&1));
+        assert!(!set.contains(&3));
+        assert_eq!(set.len(), 2);
+    }
+
+    #[test]
+    fn remove_works() {
+        let mut set: HashSet<String> = ["a", "b"].iter().map(|s| s.to_string()).collect();
+
+        assert!(set.remove("a"));
+        assert!(!set.remove("a"));
+        assert_eq!(set.len(), 1);
+    }
+}
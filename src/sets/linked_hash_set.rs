@@ -0,0 +1,234 @@
+use crate::maps::{linked_hash_map, LinkedHashMap, Order};
+use crate::sets::set_ops::{fmt_set, impl_set_operators, Set};
+use std::borrow::Borrow;
+use std::collections::hash_map::RandomState;
+use std::fmt::{self, Display};
+use std::hash::{BuildHasher, Hash};
+
+pub struct LinkedHashSet<T, S = RandomState> {
+    map: LinkedHashMap<T, (), S>,
+}
+
+impl<T> LinkedHashSet<T, RandomState> {
+    pub fn new() -> Self {
+        Self::with_hasher(RandomState::new())
+    }
+}
+
+impl<T, S> LinkedHashSet<T, S> {
+    pub fn with_hasher(hash_builder: S) -> Self {
+        LinkedHashSet {
+            map: LinkedHashMap::with_order_and_hasher(Order::Insertion, hash_builder),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.map.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.map.is_empty()
+    }
+
+    pub fn front(&self) -> Option<&T> {
+        self.map.front().map(|(value, _)| value)
+    }
+
+    pub fn back(&self) -> Option<&T> {
+        self.map.back().map(|(value, _)| value)
+    }
+
+    /// Iterates in insertion order.
+    pub fn iter(&self) -> Iter<'_, T> {
+        Iter {
+            iter: self.map.iter(),
+        }
+    }
+}
+
+impl<T, S> LinkedHashSet<T, S>
+where
+    T: Hash + Eq,
+    S: BuildHasher,
+{
+    /// Adds a value at the back, returning false if it was already present.
+    /// Re-inserting a value does not change its position.
+    pub fn insert(&mut self, value: T) -> bool {
+        if self.map.contains_key(&value) {
+            return false;
+        }
+
+        self.map.insert(value, ());
+        true
+    }
+
+    pub fn contains<Q>(&self, value: &Q) -> bool
+    where
+        T: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.map.contains_key(value)
+    }
+
+    pub fn remove<Q>(&mut self, value: &Q) -> bool
+    where
+        T: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.map.remove(value).is_some()
+    }
+
+    pub fn pop_front(&mut self) -> Option<T> {
+        self.map.pop_front().map(|(value, _)| value)
+    }
+
+    pub fn pop_back(&mut self) -> Option<T> {
+        self.map.pop_back().map(|(value, _)| value)
+    }
+
+    pub fn clear(&mut self) {
+        self.map.clear()
+    }
+}
+
+impl<T, S> Set<T> for LinkedHashSet<T, S>
+where
+    T: Hash + Eq,
+    S: BuildHasher,
+{
+    type Iter<'a>
+        = Iter<'a, T>
+    where
+        Self: 'a,
+        T: 'a;
+
+    fn len(&self) -> usize {
+        self.map.len()
+    }
+
+    fn iter(&self) -> Iter<'_, T> {
+        LinkedHashSet::iter(self)
+    }
+
+    fn contains(&self, value: &T) -> bool {
+        self.map.contains_key(value)
+    }
+}
+
+impl<T> Default for LinkedHashSet<T, RandomState> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T, S> FromIterator<T> for LinkedHashSet<T, S>
+where
+    T: Hash + Eq,
+    S: BuildHasher + Default,
+{
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut set = LinkedHashSet::with_hasher(S::default());
+        set.extend(iter);
+        set
+    }
+}
+
+impl<T, S> Extend<T> for LinkedHashSet<T, S>
+where
+    T: Hash + Eq,
+    S: BuildHasher,
+{
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for value in iter {
+            self.insert(value);
+        }
+    }
+}
+
+impl<'a, T, S> IntoIterator for &'a LinkedHashSet<T, S> {
+    type Item = &'a T;
+    type IntoIter = Iter<'a, T>;
+
+    fn into_iter(self) -> Iter<'a, T> {
+        self.iter()
+    }
+}
+
+impl_set_operators!(LinkedHashSet<T, S> where T: Hash + Eq + Clone, S: BuildHasher + Default);
+
+pub struct Iter<'a, T> {
+    iter: linked_hash_map::Iter<'a, T, ()>,
+}
+
+impl<'a, T> Iterator for Iter<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<&'a T> {
+        self.iter.next().map(|(value, _)| value)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
+impl<T> DoubleEndedIterator for Iter<'_, T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.iter.next_back().map(|(value, _)| value)
+    }
+}
+
+impl<T> ExactSizeIterator for Iter<'_, T> {}
+
+impl<T: Display, S> Display for LinkedHashSet<T, S> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt_set(f, self.iter())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::LinkedHashSet;
+    use crate::sets::Set;
+
+    fn values<'a>(iter: impl Iterator<Item = &'a i32>) -> Vec<i32> {
+        iter.copied().collect()
+    }
+
+    #[test]
+    fn insert_keeps_insertion_order() {
+        let mut set = LinkedHashSet::new();
+        assert!(set.insert(3));
+        assert!(set.insert(1));
+        assert!(set.insert(2));
+        assert!(!set.insert(3));
+        println!("set: {}", set);
+
+        assert_eq!(values(set.iter()), vec![3, 1, 2]);
+        assert_eq!(values(set.iter().rev()), vec![2, 1, 3]);
+        assert_eq!(set.front(), Some(&3));
+        assert_eq!(set.back(), Some(&2));
+    }
+
+    #[test]
+    fn remove_works() {
+        let mut set: LinkedHashSet<i32> = (0..5).collect();
+
+        assert!(set.remove(&2));
+        assert!(!set.remove(&2));
+        assert_eq!(set.pop_front(), Some(0));
+        assert_eq!(set.pop_back(), Some(4));
+        assert_eq!(values(set.iter()), vec![1, 3]);
+    }
+
+    #[test]
+    fn set_algebra_keeps_insertion_order() {
+        let a: LinkedHashSet<i32> = [5, 1, 4, 2, 3].into_iter().collect();
+        let b: LinkedHashSet<i32> = [8, 4, 6, 5, 7].into_iter().collect();
+
+        assert_eq!(values(a.union(&b)), vec![5, 1, 4, 2, 3, 8, 6, 7]);
+        assert_eq!(values(a.intersection(&b)), vec![5, 4]);
+        assert_eq!(values(a.difference(&b)), vec![1, 2, 3]);
+        assert_eq!(values(a.symmetric_difference(&b)), vec![1, 2, 3, 8, 6, 7]);
+    }
+}
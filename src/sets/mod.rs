@@ -0,0 +1,11 @@
+pub mod b_tree_set;
+pub mod hash_set;
+pub mod linked_hash_set;
+pub mod set_ops;
+pub mod tree_set;
+
+pub use self::b_tree_set::BTreeSet;
+pub use self::hash_set::HashSet;
+pub use self::linked_hash_set::LinkedHashSet;
+pub use self::set_ops::Set;
+pub use self::tree_set::TreeSet;
@@ -0,0 +1,278 @@
+use std::fmt::{self, Display};
+
+/// Read-only view shared by every set in this module, so the set algebra
+/// iterators work across set types. Bring it into scope to call `union`,
+/// `is_subset` and friends on any of the sets.
+pub trait Set<T> {
+    type Iter<'a>: Iterator<Item = &'a T>
+    where
+        Self: 'a,
+        T: 'a;
+
+    fn len(&self) -> usize;
+
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    fn iter(&self) -> Self::Iter<'_>;
+
+    fn contains(&self, value: &T) -> bool;
+
+    fn union<'a, B>(&'a self, other: &'a B) -> Union<'a, T, Self, B>
+    where
+        B: Set<T> + ?Sized,
+    {
+        Union {
+            first: self.iter(),
+            second: other.difference(self),
+        }
+    }
+
+    fn intersection<'a, B>(&'a self, other: &'a B) -> Intersection<'a, T, Self, B>
+    where
+        B: Set<T> + ?Sized,
+    {
+        Intersection {
+            iter: self.iter(),
+            other,
+        }
+    }
+
+    fn difference<'a, B>(&'a self, other: &'a B) -> Difference<'a, T, Self, B>
+    where
+        B: Set<T> + ?Sized,
+    {
+        Difference {
+            iter: self.iter(),
+            other,
+        }
+    }
+
+    fn symmetric_difference<'a, B>(&'a self, other: &'a B) -> SymmetricDifference<'a, T, Self, B>
+    where
+        B: Set<T> + ?Sized,
+    {
+        SymmetricDifference {
+            first: self.difference(other),
+            second: other.difference(self),
+        }
+    }
+
+    fn is_subset<B>(&self, other: &B) -> bool
+    where
+        B: Set<T> + ?Sized,
+    {
+        self.len() <= other.len() && self.iter().all(|value| other.contains(value))
+    }
+
+    fn is_superset<B>(&self, other: &B) -> bool
+    where
+        B: Set<T> + ?Sized,
+    {
+        other.is_subset(self)
+    }
+
+    fn is_disjoint<B>(&self, other: &B) -> bool
+    where
+        B: Set<T> + ?Sized,
+    {
+        if self.len() <= other.len() {
+            self.iter().all(|value| !other.contains(value))
+        } else {
+            other.iter().all(|value| !self.contains(value))
+        }
+    }
+}
+
+pub struct Union<'a, T: 'a, A: Set<T> + ?Sized + 'a, B: Set<T> + ?Sized + 'a> {
+    first: A::Iter<'a>,
+    second: Difference<'a, T, B, A>,
+}
+
+impl<'a, T, A, B> Iterator for Union<'a, T, A, B>
+where
+    A: Set<T> + ?Sized,
+    B: Set<T> + ?Sized,
+{
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<&'a T> {
+        self.first.next().or_else(|| self.second.next())
+    }
+}
+
+pub struct Intersection<'a, T: 'a, A: Set<T> + ?Sized + 'a, B: Set<T> + ?Sized + 'a> {
+    iter: A::Iter<'a>,
+    other: &'a B,
+}
+
+impl<'a, T, A, B> Iterator for Intersection<'a, T, A, B>
+where
+    A: Set<T> + ?Sized,
+    B: Set<T> + ?Sized,
+{
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<&'a T> {
+        let other = self.other;
+        self.iter.find(|value| other.contains(value))
+    }
+}
+
+pub struct Difference<'a, T: 'a, A: Set<T> + ?Sized + 'a, B: Set<T> + ?Sized + 'a> {
+    iter: A::Iter<'a>,
+    other: &'a B,
+}
+
+impl<'a, T, A, B> Iterator for Difference<'a, T, A, B>
+where
+    A: Set<T> + ?Sized,
+    B: Set<T> + ?Sized,
+{
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<&'a T> {
+        let other = self.other;
+        self.iter.find(|value| !other.contains(value))
+    }
+}
+
+pub struct SymmetricDifference<'a, T: 'a, A: Set<T> + ?Sized + 'a, B: Set<T> + ?Sized + 'a> {
+    first: Difference<'a, T, A, B>,
+    second: Difference<'a, T, B, A>,
+}
+
+impl<'a, T, A, B> Iterator for SymmetricDifference<'a, T, A, B>
+where
+    A: Set<T> + ?Sized,
+    B: Set<T> + ?Sized,
+{
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<&'a T> {
+        self.first.next().or_else(|| self.second.next())
+    }
+}
+
+// Implements `|`, `&`, `-` and `^` between references to a set, each
+// collecting the matching `Set` iterator into a new set.
+macro_rules! impl_set_operators {
+    ($set:ident<$($param:ident),+> where $($bounds:tt)+) => {
+        impl_set_operators!(@op BitOr, bitor, union, $set<$($param),+> where $($bounds)+);
+        impl_set_operators!(@op BitAnd, bitand, intersection, $set<$($param),+> where $($bounds)+);
+        impl_set_operators!(@op Sub, sub, difference, $set<$($param),+> where $($bounds)+);
+        impl_set_operators!(@op BitXor, bitxor, symmetric_difference, $set<$($param),+> where $($bounds)+);
+    };
+    (@op $trait:ident, $method:ident, $algebra:ident, $set:ident<$($param:ident),+> where $($bounds:tt)+) => {
+        impl<$($param),+> std::ops::$trait<&$set<$($param),+>> for &$set<$($param),+>
+        where
+            $($bounds)+
+        {
+            type Output = $set<$($param),+>;
+
+            fn $method(self, rhs: &$set<$($param),+>) -> $set<$($param),+> {
+                $crate::sets::Set::$algebra(self, rhs).cloned().collect()
+            }
+        }
+    };
+}
+
+pub(crate) use impl_set_operators;
+
+/// Writes `values` as `{a, b, c}`, the `Display` format every set shares.
+pub(crate) fn fmt_set<'a, T: Display + 'a>(
+    f: &mut fmt::Formatter<'_>,
+    values: impl IntoIterator<Item = &'a T>,
+) -> fmt::Result {
+    write!(f, "{{")?;
+    let mut iter = values.into_iter();
+
+    if let Some(value) = iter.next() {
+        write!(f, "{}", value)?;
+
+        for value in iter {
+            write!(f, ", {}", value)?;
+        }
+    }
+
+    write!(f, "}}")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Set;
+    use crate::sets::{BTreeSet, HashSet, LinkedHashSet, TreeSet};
+    use std::ops::{BitAnd, BitOr, BitXor, Sub};
+
+    fn sorted<'a>(iter: impl Iterator<Item = &'a i32>) -> Vec<i32> {
+        let mut values: Vec<i32> = iter.copied().collect();
+        values.sort();
+        values
+    }
+
+    fn check_algebra<S>()
+    where
+        S: Set<i32> + FromIterator<i32>,
+        for<'a> &'a S: BitOr<&'a S, Output = S>
+            + BitAnd<&'a S, Output = S>
+            + Sub<&'a S, Output = S>
+            + BitXor<&'a S, Output = S>,
+    {
+        let a: S = (1..=5).collect();
+        let b: S = (4..=8).collect();
+
+        assert_eq!(sorted(a.union(&b)), (1..=8).collect::<Vec<_>>());
+        assert_eq!(sorted(a.intersection(&b)), vec![4, 5]);
+        assert_eq!(sorted(a.difference(&b)), vec![1, 2, 3]);
+        assert_eq!(sorted(a.symmetric_difference(&b)), vec![1, 2, 3, 6, 7, 8]);
+
+        assert_eq!(sorted((&a | &b).iter()), (1..=8).collect::<Vec<_>>());
+        assert_eq!(sorted((&a & &b).iter()), vec![4, 5]);
+        assert_eq!(sorted((&a - &b).iter()), vec![1, 2, 3]);
+        assert_eq!(sorted((&a ^ &b).iter()), vec![1, 2, 3, 6, 7, 8]);
+
+        let small: S = (2..4).collect();
+        let large: S = (0..10).collect();
+        let other: S = (20..30).collect();
+        let empty: S = std::iter::empty().collect();
+
+        assert!(small.is_subset(&large));
+        assert!(!large.is_subset(&small));
+        assert!(large.is_superset(&small));
+        assert!(small.is_disjoint(&other));
+        assert!(!small.is_disjoint(&large));
+        assert!(empty.is_subset(&small));
+        assert!(small.is_disjoint(&empty));
+    }
+
+    #[test]
+    fn hash_set_algebra_works() {
+        check_algebra::<HashSet<i32>>();
+    }
+
+    #[test]
+    fn linked_hash_set_algebra_works() {
+        check_algebra::<LinkedHashSet<i32>>();
+    }
+
+    #[test]
+    fn tree_set_algebra_works() {
+        check_algebra::<TreeSet<i32>>();
+    }
+
+    #[test]
+    fn b_tree_set_algebra_works() {
+        check_algebra::<BTreeSet<i32>>();
+    }
+
+    #[test]
+    fn algebra_works_across_set_types() {
+        let tree: TreeSet<i32> = (0..6).collect();
+        let hash: HashSet<i32> = (3..9).collect();
+
+        let shared: Vec<i32> = tree.intersection(&hash).copied().collect();
+        assert_eq!(shared, vec![3, 4, 5]);
+        assert!(TreeSet::from_iter([4, 5]).is_subset(&hash));
+    }
+}
@@ -0,0 +1,315 @@
+use crate::maps::{tree_map, TreeMap};
+use crate::sets::set_ops::{fmt_set, impl_set_operators, Set};
+use std::borrow::Borrow;
+use std::fmt::{self, Display};
+use std::ops::RangeBounds;
+
+pub struct TreeSet<T> {
+    map: TreeMap<T, ()>,
+}
+
+impl<T> TreeSet<T> {
+    pub fn new() -> Self {
+        TreeSet {
+            map: TreeMap::new(),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.map.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.map.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.map.clear()
+    }
+
+    pub fn first(&self) -> Option<&T> {
+        self.map.first_key_value().map(|(value, _)| value)
+    }
+
+    pub fn last(&self) -> Option<&T> {
+        self.map.last_key_value().map(|(value, _)| value)
+    }
+
+    pub fn iter(&self) -> Iter<'_, T> {
+        Iter {
+            iter: self.map.iter(),
+        }
+    }
+}
+
+impl<T: Ord> TreeSet<T> {
+    /// Adds a value, returning false if it was already present.
+    pub fn insert(&mut self, value: T) -> bool {
+        if self.map.contains_key(&value) {
+            return false;
+        }
+
+        self.map.insert(value, ());
+        true
+    }
+
+    pub fn contains<Q>(&self, value: &Q) -> bool
+    where
+        T: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.map.contains_key(value)
+    }
+
+    pub fn remove<Q>(&mut self, value: &Q) -> bool
+    where
+        T: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.map.remove(value).is_some()
+    }
+
+    pub fn take<Q>(&mut self, value: &Q) -> Option<T>
+    where
+        T: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.map.remove_entry(value).map(|(value, _)| value)
+    }
+
+    pub fn pop_first(&mut self) -> Option<T> {
+        self.map.pop_first().map(|(value, _)| value)
+    }
+
+    pub fn pop_last(&mut self) -> Option<T> {
+        self.map.pop_last().map(|(value, _)| value)
+    }
+
+    pub fn range<Q, R>(&self, range: R) -> Range<'_, T>
+    where
+        T: Borrow<Q>,
+        Q: Ord + ?Sized,
+        R: RangeBounds<Q>,
+    {
+        Range {
+            range: self.map.range(range),
+        }
+    }
+
+    /// Returns everything greater than or equal to `value`, keeping the
+    /// rest. Takes O(n) time, like `TreeMap::split_off`.
+    pub fn split_off<Q>(&mut self, value: &Q) -> Self
+    where
+        T: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        TreeSet {
+            map: self.map.split_off(value),
+        }
+    }
+
+    /// Moves every value of `other` into `self` in O(n + m) time.
+    pub fn append(&mut self, other: &mut Self) {
+        self.map.append(&mut other.map)
+    }
+}
+
+impl<T: Ord> Set<T> for TreeSet<T> {
+    type Iter<'a>
+        = Iter<'a, T>
+    where
+        Self: 'a,
+        T: 'a;
+
+    fn len(&self) -> usize {
+        self.map.len()
+    }
+
+    fn iter(&self) -> Iter<'_, T> {
+        TreeSet::iter(self)
+    }
+
+    fn contains(&self, value: &T) -> bool {
+        self.map.contains_key(value)
+    }
+}
+
+impl<T> Default for TreeSet<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Ord> FromIterator<T> for TreeSet<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut set = TreeSet::new();
+        set.extend(iter);
+        set
+    }
+}
+
+impl<T: Ord> Extend<T> for TreeSet<T> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for value in iter {
+            self.insert(value);
+        }
+    }
+}
+
+impl<T> IntoIterator for TreeSet<T> {
+    type Item = T;
+    type IntoIter = IntoIter<T>;
+
+    fn into_iter(self) -> IntoIter<T> {
+        IntoIter {
+            iter: self.map.into_iter(),
+        }
+    }
+}
+
+impl<'a, T> IntoIterator for &'a TreeSet<T> {
+    type Item = &'a T;
+    type IntoIter = Iter<'a, T>;
+
+    fn into_iter(self) -> Iter<'a, T> {
+        self.iter()
+    }
+}
+
+impl_set_operators!(TreeSet<T> where T: Ord + Clone);
+
+pub struct Iter<'a, T> {
+    iter: tree_map::Iter<'a, T, ()>,
+}
+
+impl<'a, T> Iterator for Iter<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<&'a T> {
+        self.iter.next().map(|(value, _)| value)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
+impl<T> DoubleEndedIterator for Iter<'_, T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.iter.next_back().map(|(value, _)| value)
+    }
+}
+
+impl<T> ExactSizeIterator for Iter<'_, T> {}
+
+pub struct Range<'a, T> {
+    range: tree_map::Range<'a, T, ()>,
+}
+
+impl<'a, T: Ord> Iterator for Range<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<&'a T> {
+        self.range.next().map(|(value, _)| value)
+    }
+}
+
+impl<T: Ord> DoubleEndedIterator for Range<'_, T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.range.next_back().map(|(value, _)| value)
+    }
+}
+
+pub struct IntoIter<T> {
+    iter: tree_map::IntoIter<T, ()>,
+}
+
+impl<T> Iterator for IntoIter<T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        self.iter.next().map(|(value, _)| value)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
+impl<T> DoubleEndedIterator for IntoIter<T> {
+    fn next_back(&mut self) -> Option<T> {
+        self.iter.next_back().map(|(value, _)| value)
+    }
+}
+
+impl<T> ExactSizeIterator for IntoIter<T> {}
+
+impl<T: Display> Display for TreeSet<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt_set(f, self.iter())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::TreeSet;
+    use std::ops::Bound;
+
+    #[test]
+    fn insert_works() {
+        let mut set = TreeSet::new();
+        assert!(set.insert(3));
+        assert!(set.insert(1));
+        assert!(set.insert(2));
+        assert!(!set.insert(1));
+        println!("set: {}", set);
+
+        assert_eq!(set.iter().copied().collect::<Vec<_>>(), vec![1, 2, 3]);
+        assert_eq!(set.len(), 3);
+    }
+
+    #[test]
+    fn remove_works() {
+        let mut set = TreeSet::new();
+        for i in 0..5 {
+            set.insert(i);
+        }
+
+        assert!(set.remove(&2));
+        assert!(!set.remove(&2));
+        assert_eq!(set.take(&3), Some(3));
+        assert_eq!(set.pop_first(), Some(0));
+        assert_eq!(set.pop_last(), Some(4));
+        assert_eq!(set.into_iter().collect::<Vec<_>>(), vec![1]);
+    }
+
+    #[test]
+    fn range_works() {
+        let mut set = TreeSet::new();
+        for word in ["pear", "apple", "fig", "kiwi", "date"] {
+            set.insert(String::from(word));
+        }
+
+        let bounds = (Bound::Included("b"), Bound::Excluded("g"));
+        let words: Vec<&str> = set.range::<str, _>(bounds).map(|s| s.as_str()).collect();
+        assert_eq!(words, vec!["date", "fig"]);
+        assert_eq!(set.first().map(|s| s.as_str()), Some("apple"));
+        assert_eq!(set.last().map(|s| s.as_str()), Some("pear"));
+    }
+
+    #[test]
+    fn split_off_and_append_work() {
+        let mut set = TreeSet::new();
+        for i in 0..10 {
+            set.insert(i);
+        }
+
+        let mut high = set.split_off(&7);
+        assert!(set.iter().copied().eq(0..7));
+        assert!(high.iter().copied().eq(7..10));
+
+        set.append(&mut high);
+        assert!(high.is_empty());
+        assert!(set.iter().copied().eq(0..10));
+    }
+}
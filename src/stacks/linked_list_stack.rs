@@ -1,6 +1,8 @@
 use std::collections::LinkedList;
-use std::fmt::{self, Display};
+use std::fmt::{self, Debug, Display};
+use std::ops::Index;
 
+#[derive(Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
 pub struct LinkedListStack<T: Copy + PartialEq> {
     buffer: LinkedList<T>,
 }
@@ -33,10 +35,43 @@ impl<T: Copy + PartialEq> LinkedListStack<T> {
     }
 
     pub fn collect(&self) -> Vec<T> {
-        self.buffer
-            .iter()
-            .map(|elm| elm.clone())
-            .collect::<Vec<T>>()
+        self.buffer.iter().copied().collect::<Vec<T>>()
+    }
+}
+
+impl<T: Copy + PartialEq> Default for LinkedListStack<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Copy + PartialEq> FromIterator<T> for LinkedListStack<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut stack = Self::new();
+        stack.extend(iter);
+        stack
+    }
+}
+
+impl<T: Copy + PartialEq> Extend<T> for LinkedListStack<T> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for elt in iter {
+            self.push(elt);
+        }
+    }
+}
+
+impl<T: Copy + PartialEq> Index<usize> for LinkedListStack<T> {
+    type Output = T;
+
+    fn index(&self, index: usize) -> &T {
+        self.buffer.iter().nth(index).expect("Index out of bounds")
+    }
+}
+
+impl<T: Copy + PartialEq + Debug> Debug for LinkedListStack<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.buffer.iter()).finish()
     }
 }
 
@@ -60,6 +95,7 @@ impl<T: Copy + PartialEq + Display> Display for LinkedListStack<T> {
 #[cfg(test)]
 mod tests {
     use super::LinkedListStack;
+    use crate::stacks::VecStack;
 
     #[test]
     fn push_works() {
@@ -111,4 +147,23 @@ mod tests {
 
         assert_eq!(stack.len(), 0);
     }
+
+    #[test]
+    fn agrees_with_vec_stack() {
+        let mut stack: LinkedListStack<i32> = (1..=3).collect();
+        let mut vec_stack: VecStack<i32> = (1..=3).collect();
+        stack.pop();
+        vec_stack.pop();
+        stack.extend([4, 5]);
+        vec_stack.extend([4, 5]);
+
+        assert_eq!(stack.top(), vec_stack.top());
+        assert_eq!(stack[0], vec_stack[0]);
+        assert_eq!(stack[3], vec_stack[3]);
+        assert_eq!(format!("{:?}", stack), format!("{:?}", vec_stack));
+
+        let shorter: LinkedListStack<i32> = [1, 2, 4].into_iter().collect();
+        assert!(shorter < stack);
+        assert_eq!(stack.clone(), stack);
+    }
 }
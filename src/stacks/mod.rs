@@ -1,5 +1,5 @@
-mod linked_list_stack;
-mod vec_stack;
+pub mod linked_list_stack;
+pub mod vec_stack;
 
 pub use self::linked_list_stack::LinkedListStack;
 pub use self::vec_stack::VecStack;
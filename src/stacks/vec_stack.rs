@@ -1,5 +1,7 @@
-use std::fmt::{self, Display};
+use std::fmt::{self, Debug, Display};
+use std::ops::Index;
 
+#[derive(Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
 pub struct VecStack<T: Copy + PartialEq> {
     buffer: Vec<T>,
 }
@@ -30,10 +32,43 @@ impl<T: Copy + PartialEq> VecStack<T> {
     }
 
     pub fn collect(&self) -> Vec<T> {
-        self.buffer
-            .iter()
-            .map(|elm| elm.clone())
-            .collect::<Vec<T>>()
+        self.buffer.to_vec()
+    }
+}
+
+impl<T: Copy + PartialEq> Default for VecStack<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Copy + PartialEq> FromIterator<T> for VecStack<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut stack = Self::new();
+        stack.extend(iter);
+        stack
+    }
+}
+
+impl<T: Copy + PartialEq> Extend<T> for VecStack<T> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for elt in iter {
+            self.push(elt);
+        }
+    }
+}
+
+impl<T: Copy + PartialEq> Index<usize> for VecStack<T> {
+    type Output = T;
+
+    fn index(&self, index: usize) -> &T {
+        &self.buffer[index]
+    }
+}
+
+impl<T: Copy + PartialEq + Debug> Debug for VecStack<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.buffer.iter()).finish()
     }
 }
 
@@ -57,6 +92,7 @@ impl<T: Copy + PartialEq + Display> Display for VecStack<T> {
 #[cfg(test)]
 mod tests {
     use super::VecStack;
+    use crate::test_util::hash_of;
 
     #[test]
     fn push_works() {
@@ -108,4 +144,22 @@ mod tests {
 
         assert_eq!(stack.len(), 0);
     }
+
+    #[test]
+    fn comparisons_start_from_the_bottom() {
+        let mut stack: VecStack<i32> = (1..=3).collect();
+        assert_eq!(stack[0], 1);
+        assert_eq!(format!("{:?}", stack), "[1, 2, 3]");
+
+        // A taller stack with a smaller bottom element still sorts first.
+        let taller: VecStack<i32> = [0, 9, 9, 9].into_iter().collect();
+        assert!(taller < stack);
+
+        let copy = stack.clone();
+        stack.push(4);
+        assert!(copy < stack);
+        stack.pop();
+        assert_eq!(stack, copy);
+        assert_eq!(hash_of(&stack), hash_of(&copy));
+    }
 }
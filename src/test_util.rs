@@ -0,0 +1,44 @@
+//! Helpers shared by the unit tests across the crate.
+
+use std::cell::Cell;
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+
+pub(crate) fn hash_of<T: Hash + ?Sized>(value: &T) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    value.hash(&mut hasher);
+    hasher.finish()
+}
+
+/// Bumps a shared counter when dropped, so tests can check that a collection
+/// drops each element exactly once.
+pub(crate) struct DropCounter<'a> {
+    drops: &'a Cell<usize>,
+    panics: bool,
+}
+
+impl<'a> DropCounter<'a> {
+    pub(crate) fn new(drops: &'a Cell<usize>) -> Self {
+        DropCounter {
+            drops,
+            panics: false,
+        }
+    }
+
+    /// Counts its drop like any other, then panics.
+    pub(crate) fn panicking(drops: &'a Cell<usize>) -> Self {
+        DropCounter {
+            drops,
+            panics: true,
+        }
+    }
+}
+
+impl Drop for DropCounter<'_> {
+    fn drop(&mut self) {
+        self.drops.set(self.drops.get() + 1);
+        if self.panics {
+            panic!("DropCounter panicked on drop");
+        }
+    }
+}
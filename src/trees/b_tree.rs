@@ -1,20 +1,30 @@
+use std::cmp::Ordering;
 use std::convert::TryFrom;
-use std::fmt::Debug;
+use std::fmt::{self, Debug};
+use std::hash::{Hash, Hasher};
 use std::mem;
+use std::ops::Index;
 
+pub(crate) const DEFAULT_BRANCH_FACTOR: usize = 6;
+
+#[derive(Clone)]
 struct Node<T> {
     keys: Vec<T>,
     children: Vec<Node<T>>,
 }
 
+#[derive(Clone)]
 pub struct BTree<T> {
     root: Node<T>,
     props: BTreeProps,
+    len: usize,
 }
 
+#[derive(Clone)]
 struct BTreeProps {
     degree: usize,
     max_keys: usize,
+    min_keys: usize,
     mid_key_index: usize,
 }
 
@@ -26,11 +36,11 @@ where
         Node {
             keys: match keys {
                 Some(keys) => keys,
-                None => Vec::with_capacity(degree - 1),
+                None => Vec::with_capacity(degree),
             },
             children: match children {
                 Some(children) => children,
-                None => Vec::with_capacity(degree),
+                None => Vec::with_capacity(degree + 1),
             },
         }
     }
@@ -45,54 +55,120 @@ impl BTreeProps {
         BTreeProps {
             degree,
             max_keys: degree - 1,
+            min_keys: degree.div_ceil(2) - 1,
             mid_key_index: (degree - 1) / 2,
         }
     }
 
-    fn is_full<T: Ord + Copy>(&self, node: &Node<T>) -> bool {
-        node.keys.len() == self.max_keys
-    }
-
-    fn split_child<T: Ord + Copy + Default>(&self, parent: &mut Node<T>, child_index: usize) {
-        let child = &mut parent.children[child_index];
-        let middle_key = child.keys[self.mid_key_index];
-        let right_keys = match child.keys.split_off(self.mid_key_index).split_first() {
-            Some((_, others)) => others.to_vec(),
-            None => Vec::with_capacity(self.max_keys),
-        };
+    // Inserts into the subtree at `node`. If that leaves it one key over full,
+    // splits it and returns the middle key and the new right half for the
+    // parent to take in.
+    fn insert_into<T: Ord + Copy>(&self, node: &mut Node<T>, key: T) -> Option<(T, Node<T>)> {
+        let index = node.keys.partition_point(|k| *k < key);
+        if node.is_leaf() {
+            node.keys.insert(index, key);
+        } else if let Some((middle_key, right)) = self.insert_into(&mut node.children[index], key) {
+            node.keys.insert(index, middle_key);
+            node.children.insert(index + 1, right);
+        }
 
-        let right_children = if !child.is_leaf() {
-            Some(child.children.split_off(self.mid_key_index + 1))
+        if node.keys.len() > self.max_keys {
+            Some(self.split(node))
         } else {
             None
-        };
+        }
+    }
 
-        let new_child_node: Node<T> = Node::new(self.degree, Some(right_keys), right_children);
+    fn split<T: Ord>(&self, node: &mut Node<T>) -> (T, Node<T>) {
+        let right_keys = node.keys.split_off(self.mid_key_index + 1);
+        let middle_key = node.keys.pop().unwrap();
+        let right_children = if node.is_leaf() {
+            None
+        } else {
+            Some(node.children.split_off(self.mid_key_index + 1))
+        };
 
-        parent.keys.insert(child_index, middle_key);
-        parent.children.insert(child_index + 1, new_child_node);
+        (middle_key, Node::new(self.degree, Some(right_keys), right_children))
     }
 
-    fn insert_non_full<T: Ord + Copy + Default>(&mut self, node: &mut Node<T>, key: T) {
-        let mut index: isize = isize::try_from(node.keys.len()).ok().unwrap() - 1;
-        while index >= 0 && node.keys[index as usize] >= key {
-            index -= 1;
+    // Removes `key` from the subtree at `node`, which may be left one key
+    // short for its parent to rebalance.
+    fn remove_from<T: Ord + Copy>(&self, node: &mut Node<T>, key: T) -> bool {
+        let index = node.keys.partition_point(|k| *k < key);
+
+        if index < node.keys.len() && node.keys[index] == key {
+            if node.is_leaf() {
+                node.keys.remove(index);
+            } else {
+                node.keys[index] = self.remove_max(&mut node.children[index]);
+                self.rebalance(node, index);
+            }
+            return true;
+        }
+
+        if node.is_leaf() || !self.remove_from(&mut node.children[index], key) {
+            return false;
         }
+        self.rebalance(node, index);
+        true
+    }
 
-        let mut index: usize = usize::try_from(index + 1).ok().unwrap();
+    fn remove_max<T: Ord + Copy>(&self, node: &mut Node<T>) -> T {
         if node.is_leaf() {
-            node.keys.insert(index, key);
-        } else {
-            if self.is_full(&node.children[index]) {
-                self.split_child(node, index);
-                if node.keys[index] < key {
-                    index += 1;
-                }
+            return node.keys.pop().unwrap();
+        }
+
+        let index = node.children.len() - 1;
+        let key = self.remove_max(&mut node.children[index]);
+        self.rebalance(node, index);
+        key
+    }
+
+    // Tops the child at `index` back up to `min_keys` after a removal, by
+    // borrowing through the parent from a sibling that can spare a key or
+    // else by merging with a sibling.
+    fn rebalance<T: Ord + Copy>(&self, node: &mut Node<T>, index: usize) {
+        if node.children[index].keys.len() >= self.min_keys {
+            return;
+        }
+
+        if index > 0 && node.children[index - 1].keys.len() > self.min_keys {
+            let (left, right) = node.children.split_at_mut(index);
+            let sibling = &mut left[index - 1];
+            let child = &mut right[0];
+
+            child.keys.insert(0, node.keys[index - 1]);
+            node.keys[index - 1] = sibling.keys.pop().unwrap();
+            if !sibling.is_leaf() {
+                child.children.insert(0, sibling.children.pop().unwrap());
             }
+        } else if index < node.keys.len() && node.children[index + 1].keys.len() > self.min_keys {
+            let (left, right) = node.children.split_at_mut(index + 1);
+            let child = &mut left[index];
+            let sibling = &mut right[0];
 
-            self.insert_non_full(&mut node.children[index], key);
+            child.keys.push(node.keys[index]);
+            node.keys[index] = sibling.keys.remove(0);
+            if !sibling.is_leaf() {
+                child.children.push(sibling.children.remove(0));
+            }
+        } else if index < node.keys.len() {
+            Self::merge_children(node, index);
+        } else {
+            Self::merge_children(node, index - 1);
         }
     }
+
+    fn merge_children<T>(node: &mut Node<T>, index: usize) {
+        let right = node.children.remove(index + 1);
+        let middle_key = node.keys.remove(index);
+        let child = &mut node.children[index];
+
+        child.keys.push(middle_key);
+        child.keys.extend(right.keys);
+        child.children.extend(right.children);
+    }
+
     fn traverse_node<T: Ord + Debug>(node: &Node<T>, mut depth: usize) {
         if node.is_leaf() {
             print!(" {0:{<1$}{2:?}{0:}<1$} ", "", depth, node.keys);
@@ -107,26 +183,61 @@ impl BTreeProps {
     }
 }
 
+impl<T> BTree<T> {
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn iter(&self) -> Iter<'_, T> {
+        let mut iter = Iter {
+            stack: Vec::new(),
+            len: self.len,
+        };
+        iter.push_left_edge(&self.root);
+        iter
+    }
+}
+
 impl<T> BTree<T>
 where
     T: Ord + Copy + Debug + Default,
 {
     pub fn new(branch_factor: usize) -> Self {
-        let degree = 2 * branch_factor;
+        Self::with_degree(2 * branch_factor)
+    }
+
+    fn with_degree(degree: usize) -> Self {
         BTree {
             root: Node::new(degree, None, None),
             props: BTreeProps::new(degree),
+            len: 0,
         }
     }
 
     pub fn insert(&mut self, key: T) {
-        if self.props.is_full(&self.root) {
-            let mut new_root = Node::new(self.props.degree, None, None);
-            mem::swap(&mut new_root, &mut self.root);
-            self.root.children.insert(0, new_root);
-            self.props.split_child(&mut self.root, 0);
+        if let Some((middle_key, right)) = self.props.insert_into(&mut self.root, key) {
+            let left = mem::replace(&mut self.root, Node::new(self.props.degree, None, None));
+            self.root.keys.push(middle_key);
+            self.root.children.push(left);
+            self.root.children.push(right);
         }
-        self.props.insert_non_full(&mut self.root, key);
+        self.len += 1;
+    }
+
+    pub fn remove(&mut self, key: T) -> bool {
+        let removed = self.props.remove_from(&mut self.root, key);
+        if self.root.keys.is_empty() && !self.root.is_leaf() {
+            self.root = self.root.children.remove(0);
+        }
+
+        if removed {
+            self.len -= 1;
+        }
+        removed
     }
 
     pub fn traverse(&self) {
@@ -155,9 +266,156 @@ where
     }
 }
 
+impl<T> Default for BTree<T>
+where
+    T: Ord + Copy + Debug + Default,
+{
+    fn default() -> Self {
+        Self::new(DEFAULT_BRANCH_FACTOR)
+    }
+}
+
+impl<T> FromIterator<T> for BTree<T>
+where
+    T: Ord + Copy + Debug + Default,
+{
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut tree = BTree::default();
+        tree.extend(iter);
+        tree
+    }
+}
+
+impl<T> Extend<T> for BTree<T>
+where
+    T: Ord + Copy + Debug + Default,
+{
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for key in iter {
+            self.insert(key);
+        }
+    }
+}
+
+/// Returns the `index`-th smallest key. This walks the tree in order, so it
+/// is O(n).
+impl<T> Index<usize> for BTree<T> {
+    type Output = T;
+
+    fn index(&self, index: usize) -> &T {
+        self.iter().nth(index).expect("Index out of bounds")
+    }
+}
+
+impl<T: Debug> Debug for BTree<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_set().entries(self.iter()).finish()
+    }
+}
+
+// Trees compare by their keys in order, so the branch factor and the shape
+// the insertions left behind do not matter.
+impl<T: PartialEq> PartialEq for BTree<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.len == other.len && self.iter().eq(other.iter())
+    }
+}
+
+impl<T: Eq> Eq for BTree<T> {}
+
+impl<T: Hash> Hash for BTree<T> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.len.hash(state);
+        for key in self.iter() {
+            key.hash(state);
+        }
+    }
+}
+
+impl<T: PartialOrd> PartialOrd for BTree<T> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        self.iter().partial_cmp(other.iter())
+    }
+}
+
+impl<T: Ord> Ord for BTree<T> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.iter().cmp(other.iter())
+    }
+}
+
+pub struct Iter<'a, T> {
+    // Each entry is a node and the index of the next key to yield from it.
+    stack: Vec<(&'a Node<T>, usize)>,
+    len: usize,
+}
+
+impl<'a, T> Iter<'a, T> {
+    fn push_left_edge(&mut self, mut node: &'a Node<T>) {
+        loop {
+            self.stack.push((node, 0));
+            match node.children.first() {
+                Some(child) => node = child,
+                None => break,
+            }
+        }
+    }
+}
+
+impl<'a, T> Iterator for Iter<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<&'a T> {
+        loop {
+            let (node, index) = self.stack.last_mut()?;
+            let node: &'a Node<T> = node;
+
+            if *index < node.keys.len() {
+                let key = &node.keys[*index];
+                *index += 1;
+                if let Some(child) = node.children.get(*index) {
+                    self.push_left_edge(child);
+                }
+                self.len -= 1;
+                return Some(key);
+            }
+
+            self.stack.pop();
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.len, Some(self.len))
+    }
+}
+
+impl<T> ExactSizeIterator for Iter<'_, T> {}
+
 #[cfg(test)]
 mod test {
-    use super::BTree;
+    use super::{BTree, BTreeProps, Node};
+    use crate::test_util::hash_of;
+
+    // Checks every node's key count against the degree and that all leaves
+    // sit at the same depth, which is returned.
+    fn check_occupancy<T: Ord>(node: &Node<T>, props: &BTreeProps, is_root: bool) -> usize {
+        assert!(node.keys.len() <= props.max_keys);
+        if !is_root {
+            assert!(node.keys.len() >= props.min_keys);
+        }
+        if node.is_leaf() {
+            return 0;
+        }
+
+        assert_eq!(node.children.len(), node.keys.len() + 1);
+        let depths: Vec<usize> = node
+            .children
+            .iter()
+            .map(|child| check_occupancy(child, props, false))
+            .collect();
+        assert!(depths.iter().all(|&depth| depth == depths[0]));
+        depths[0] + 1
+    }
 
     #[test]
     fn test_search() {
@@ -174,4 +432,91 @@ mod test {
         assert!(tree.search(15));
         assert!(!tree.search(16));
     }
+
+    #[test]
+    fn test_iter() {
+        let mut tree = BTree::new(2);
+        for key in [10, 20, 30, 5, 6, 7, 11, 12, 15] {
+            tree.insert(key);
+        }
+
+        let keys: Vec<i32> = tree.iter().copied().collect();
+        assert_eq!(keys, vec![5, 6, 7, 10, 11, 12, 15, 20, 30]);
+        assert_eq!(tree.iter().len(), 9);
+        assert_eq!(tree.len(), 9);
+    }
+
+    #[test]
+    fn test_remove() {
+        let mut tree = BTree::new(2);
+        for key in 0..100 {
+            tree.insert(key);
+        }
+
+        for key in (0..100).filter(|key| key % 3 != 0) {
+            assert!(tree.remove(key));
+        }
+        assert!(!tree.remove(1));
+
+        assert!(tree.iter().copied().eq((0..100).step_by(3)));
+        assert_eq!(tree.len(), 34);
+        assert!(tree.search(99));
+        assert!(!tree.search(98));
+
+        for key in (0..100).step_by(3) {
+            assert!(tree.remove(key));
+        }
+        assert!(tree.is_empty());
+        assert_eq!(tree.iter().next(), None);
+    }
+
+    #[test]
+    fn test_remove_keeps_occupancy_for_odd_degrees() {
+        for degree in [3, 5] {
+            let mut tree = BTree::with_degree(degree);
+            assert_eq!(tree.props.min_keys, degree.div_ceil(2) - 1);
+
+            // Interleave the keys so removals hit inner nodes as well as leaves.
+            let keys: Vec<i32> = (0..200).map(|key| key * 37 % 200).collect();
+            for &key in &keys {
+                tree.insert(key);
+                check_occupancy(&tree.root, &tree.props, true);
+            }
+
+            for &key in keys.iter().step_by(2) {
+                assert!(tree.remove(key));
+                check_occupancy(&tree.root, &tree.props, true);
+            }
+            let mut kept: Vec<i32> = keys.iter().copied().skip(1).step_by(2).collect();
+            kept.sort();
+            assert!(tree.iter().copied().eq(kept.iter().copied()));
+
+            for &key in &kept {
+                assert!(tree.remove(key));
+                check_occupancy(&tree.root, &tree.props, true);
+            }
+            assert!(tree.is_empty());
+        }
+    }
+
+    #[test]
+    fn test_std_traits() {
+        let tree: BTree<i32> = [5, 1, 4, 2, 3].into_iter().collect();
+        assert_eq!(format!("{:?}", tree), "{1, 2, 3, 4, 5}");
+        assert_eq!(tree[0], 1);
+        assert_eq!(tree[4], 5);
+
+        // A different branch factor gives a different shape, but the same keys.
+        let mut other = BTree::new(2);
+        other.extend(1..=5);
+        assert_eq!(tree, other);
+        assert_eq!(hash_of(&tree), hash_of(&other));
+
+        let mut copy = other.clone();
+        copy.remove(3);
+        assert!(other.search(3));
+        assert_ne!(copy, other);
+        assert!(other < copy);
+        assert_eq!(BTree::<i32>::default(), BTree::new(2));
+    }
 }
@@ -1,3 +1,3 @@
-mod b_tree;
+pub mod b_tree;
 
 pub use self::b_tree::BTree;
@@ -0,0 +1,48 @@
+//! Differential tests: random operation sequences are applied to each dust
+//! collection and to its std counterpart, which must stay in the same state
+//! throughout. The operations live in `model` so the fuzz targets can reuse
+//! them.
+
+mod model;
+
+use arbitrary::{Arbitrary, Unstructured};
+use proptest::prelude::*;
+
+// proptest generates and shrinks raw bytes; `arbitrary` turns them into
+// operations the same way the fuzzer does.
+fn decode<'a, T: Arbitrary<'a>>(bytes: &'a [u8]) -> T {
+    T::arbitrary_take_rest(Unstructured::new(bytes)).expect("decoding never fails on byte input")
+}
+
+fn bytes() -> impl Strategy<Value = Vec<u8>> {
+    prop::collection::vec(any::<u8>(), 0..2048)
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(512))]
+
+    #[test]
+    fn vec_matches_std(bytes in bytes()) {
+        model::run_vec(&decode::<Vec<model::VecOp>>(&bytes));
+    }
+
+    #[test]
+    fn small_vec_matches_std(bytes in bytes()) {
+        model::run_small_vec(&decode::<Vec<model::VecOp>>(&bytes));
+    }
+
+    #[test]
+    fn singly_linked_list_matches_std(bytes in bytes()) {
+        model::run_singly_linked_list(&decode::<Vec<model::SinglyOp>>(&bytes));
+    }
+
+    #[test]
+    fn doubly_linked_list_matches_std(bytes in bytes()) {
+        model::run_doubly_linked_list(&decode::<Vec<model::DoublyOp>>(&bytes));
+    }
+
+    #[test]
+    fn vec_deque_matches_std(bytes in bytes()) {
+        model::run_vec_deque(&decode::<Vec<model::DequeOp>>(&bytes));
+    }
+}
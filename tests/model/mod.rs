@@ -0,0 +1,524 @@
+//! Operation sequences shared by the differential tests and the fuzz targets.
+//!
+//! Each `run_*` function applies the same operations to a dust collection and
+//! to the std collection it mirrors, and panics as soon as their observable
+//! state differs. Indices are taken modulo the current length so that every
+//! generated operation is valid.
+
+use arbitrary::Arbitrary;
+use dust::deques::VecDeque as DustVecDeque;
+use dust::lists::{DoublyLinkedList, SinglyLinkedList, SmallVec, Vec as DustVec};
+use std::collections::VecDeque;
+
+// Elements own a heap allocation, so a double drop or a use after free shows
+// up as a crash instead of passing silently.
+fn elem(value: u8) -> String {
+    (value % 16).to_string()
+}
+
+fn elems(values: &[u8]) -> Vec<String> {
+    values.iter().copied().map(elem).collect()
+}
+
+// A position between elements, in `0..=len`.
+fn gap(index: u8, len: usize) -> usize {
+    index as usize % (len + 1)
+}
+
+// A position of an element, in `0..len`. Only valid for non-empty collections.
+fn slot(index: u8, len: usize) -> usize {
+    index as usize % len
+}
+
+#[derive(Arbitrary, Debug, Clone)]
+pub enum VecOp {
+    Push(u8),
+    Pop,
+    Insert(u8, u8),
+    Remove(u8),
+    SwapRemove(u8),
+    Truncate(u8),
+    Clear,
+    Reserve(u8),
+    ReserveExact(u8),
+    ShrinkToFit,
+    Retain,
+    Dedup,
+    /// Drains `start..end`, consuming only `take` items before dropping the
+    /// iterator.
+    Drain {
+        start: u8,
+        end: u8,
+        take: u8,
+    },
+    /// Splits at `at` and appends the tail straight back.
+    SplitOffAppend(u8),
+    Extend(Vec<u8>),
+}
+
+// Vec and SmallVec share their whole API, so one body drives both.
+macro_rules! apply_vec_op {
+    ($vec:ident, $model:ident, $op:expr) => {
+        match *$op {
+            VecOp::Push(value) => {
+                $vec.push(elem(value));
+                $model.push(elem(value));
+            }
+            VecOp::Pop => assert_eq!($vec.pop(), $model.pop()),
+            VecOp::Insert(index, value) => {
+                let index = gap(index, $model.len());
+                $vec.insert(index, elem(value));
+                $model.insert(index, elem(value));
+            }
+            VecOp::Remove(index) if !$model.is_empty() => {
+                let index = slot(index, $model.len());
+                assert_eq!($vec.remove(index), $model.remove(index));
+            }
+            VecOp::SwapRemove(index) if !$model.is_empty() => {
+                let index = slot(index, $model.len());
+                assert_eq!($vec.swap_remove(index), $model.swap_remove(index));
+            }
+            VecOp::Remove(_) | VecOp::SwapRemove(_) => {}
+            VecOp::Truncate(len) => {
+                $vec.truncate(len as usize);
+                $model.truncate(len as usize);
+            }
+            VecOp::Clear => {
+                $vec.clear();
+                $model.clear();
+            }
+            VecOp::Reserve(additional) => {
+                $vec.reserve(additional as usize);
+                assert!($vec.capacity() >= $vec.len() + additional as usize);
+            }
+            VecOp::ReserveExact(additional) => {
+                $vec.reserve_exact(additional as usize);
+                assert!($vec.capacity() >= $vec.len() + additional as usize);
+            }
+            VecOp::ShrinkToFit => $vec.shrink_to_fit(),
+            VecOp::Retain => {
+                $vec.retain(|s| s.len() == 1);
+                $model.retain(|s| s.len() == 1);
+            }
+            VecOp::Dedup => {
+                $vec.dedup();
+                $model.dedup();
+            }
+            VecOp::Drain { start, end, take } => {
+                let start = gap(start, $model.len());
+                let end = start + gap(end, $model.len() - start);
+                let drained: Vec<String> = $vec.drain(start..end).take(take as usize).collect();
+                let expected: Vec<String> = $model.drain(start..end).take(take as usize).collect();
+                assert_eq!(drained, expected);
+            }
+            VecOp::SplitOffAppend(at) => {
+                let at = gap(at, $model.len());
+                let mut tail = $vec.split_off(at);
+                assert_eq!(&$vec[..], &$model[..at]);
+                assert_eq!(&tail[..], &$model[at..]);
+                $vec.append(&mut tail);
+                assert!(tail.is_empty());
+            }
+            VecOp::Extend(ref values) => {
+                $vec.extend(elems(values));
+                $model.extend(elems(values));
+            }
+        }
+    };
+}
+
+pub fn run_vec(ops: &[VecOp]) {
+    let mut vec: DustVec<String> = DustVec::new();
+    let mut model: Vec<String> = Vec::new();
+
+    for op in ops {
+        apply_vec_op!(vec, model, op);
+        assert_eq!(&vec[..], &model[..], "after {:?}", op);
+        assert!(vec.capacity() >= vec.len());
+    }
+}
+
+pub fn run_small_vec(ops: &[VecOp]) {
+    let mut vec: SmallVec<String, 4> = SmallVec::new();
+    let mut model: Vec<String> = Vec::new();
+
+    for op in ops {
+        apply_vec_op!(vec, model, op);
+        assert_eq!(&vec[..], &model[..], "after {:?}", op);
+        assert!(vec.capacity() >= vec.len());
+        assert!(vec.spilled() || vec.capacity() == 4);
+    }
+}
+
+/// Operations both linked lists support.
+#[derive(Arbitrary, Debug, Clone)]
+pub enum ListOp {
+    PushFront(u8),
+    PushBack(u8),
+    PopFront,
+    PopBack,
+    Insert(u8, u8),
+    Remove(u8),
+    Get(u8),
+    /// Overwrites the element at the given index through `get_mut`.
+    Set(u8, u8),
+    SortByKey,
+    Sort,
+    /// Sorts the list and merges in the sorted values.
+    Merge(Vec<u8>),
+    Extend(Vec<u8>),
+}
+
+macro_rules! apply_list_op {
+    ($list:ident, $model:ident, $op:expr) => {
+        match *$op {
+            ListOp::PushFront(value) => {
+                $list.push_front(elem(value));
+                $model.push_front(elem(value));
+            }
+            ListOp::PushBack(value) => {
+                $list.push_back(elem(value));
+                $model.push_back(elem(value));
+            }
+            ListOp::PopFront => assert_eq!($list.pop_front(), $model.pop_front()),
+            ListOp::PopBack => assert_eq!($list.pop_back(), $model.pop_back()),
+            ListOp::Insert(index, value) => {
+                let index = gap(index, $model.len());
+                $list.insert(index, elem(value));
+                $model.insert(index, elem(value));
+            }
+            ListOp::Remove(index) if !$model.is_empty() => {
+                let index = slot(index, $model.len());
+                assert_eq!($list.remove(index), $model.remove(index));
+            }
+            ListOp::Get(index) if !$model.is_empty() => {
+                let index = slot(index, $model.len());
+                assert_eq!($list.get(index), $model.get(index));
+            }
+            ListOp::Set(index, value) if !$model.is_empty() => {
+                let index = slot(index, $model.len());
+                *$list.get_mut(index).unwrap() = elem(value);
+                $model[index] = elem(value);
+            }
+            ListOp::Remove(_) | ListOp::Get(_) | ListOp::Set(..) => {}
+            ListOp::SortByKey => {
+                // Keys collide a lot, so this also checks stability.
+                $list.sort_by_key(|s| s.len());
+                $model.make_contiguous().sort_by_key(|s| s.len());
+            }
+            ListOp::Sort => {
+                $list.sort();
+                $model.make_contiguous().sort();
+            }
+            ListOp::Merge(ref values) => {
+                let mut other: Vec<String> = elems(values);
+                other.sort();
+                $list.sort();
+                $list.merge(&mut other.iter().cloned().collect());
+
+                // A stable sort of the concatenation favours `self` on ties,
+                // just like `merge`.
+                $model.extend(other);
+                $model.make_contiguous().sort();
+            }
+            ListOp::Extend(ref values) => {
+                $list.extend(elems(values));
+                $model.extend(elems(values));
+            }
+        }
+    };
+}
+
+#[derive(Arbitrary, Debug, Clone)]
+pub enum SinglyOp {
+    List(ListOp),
+    Reverse,
+    /// Splits at `at` and checks both halves, then appends the tail back.
+    SplitOffAppend(u8),
+    Append(Vec<u8>),
+}
+
+pub fn run_singly_linked_list(ops: &[SinglyOp]) {
+    let mut list: SinglyLinkedList<String> = SinglyLinkedList::new();
+    let mut model: VecDeque<String> = VecDeque::new();
+
+    for op in ops {
+        match op {
+            SinglyOp::List(op) => apply_list_op!(list, model, op),
+            SinglyOp::Reverse => {
+                list.reverse();
+                model.make_contiguous().reverse();
+            }
+            SinglyOp::SplitOffAppend(at) => {
+                let at = gap(*at, model.len());
+                let mut tail = list.split_off(at);
+                assert!(list.iter().eq(model.range(..at)));
+                assert!(tail.iter().eq(model.range(at..)));
+                assert_eq!(tail.back(), model.back().filter(|_| at < model.len()));
+                list.append(&mut tail);
+                assert!(tail.is_empty());
+            }
+            SinglyOp::Append(values) => {
+                list.append(&mut elems(values).into_iter().collect());
+                model.extend(elems(values));
+            }
+        }
+
+        assert_eq!(list.len(), model.len(), "after {:?}", op);
+        assert!(list.iter().eq(model.iter()), "after {:?}", op);
+        assert_eq!(list.front(), model.front());
+        assert_eq!(list.back(), model.back());
+    }
+}
+
+#[derive(Arbitrary, Debug, Clone)]
+pub enum CursorOp {
+    MoveNext,
+    MovePrev,
+    InsertAfter(u8),
+    InsertBefore(u8),
+    RemoveCurrent,
+    SplitAfter,
+    SplitBefore,
+    SpliceAfter(Vec<u8>),
+    SpliceBefore(Vec<u8>),
+}
+
+#[derive(Arbitrary, Debug, Clone)]
+pub enum DoublyOp {
+    List(ListOp),
+    /// Runs a sequence of cursor operations, starting from the back if
+    /// `from_back` is set.
+    Cursor {
+        from_back: bool,
+        ops: Vec<CursorOp>,
+    },
+}
+
+// Mirrors a cursor over `model`; `None` is the ghost position.
+fn apply_cursor_op(model: &mut VecDeque<String>, index: &mut Option<usize>, op: &CursorOp) {
+    let len = model.len();
+    match *op {
+        CursorOp::MoveNext => {
+            *index = match *index {
+                None if len > 0 => Some(0),
+                Some(i) if i + 1 < len => Some(i + 1),
+                _ => None,
+            }
+        }
+        CursorOp::MovePrev => {
+            *index = match *index {
+                None if len > 0 => Some(len - 1),
+                Some(i) if i > 0 => Some(i - 1),
+                _ => None,
+            }
+        }
+        CursorOp::InsertAfter(value) => match *index {
+            None => model.push_front(elem(value)),
+            Some(i) => model.insert(i + 1, elem(value)),
+        },
+        CursorOp::InsertBefore(value) => match index {
+            None => model.push_back(elem(value)),
+            Some(i) => {
+                model.insert(*i, elem(value));
+                *i += 1;
+            }
+        },
+        CursorOp::RemoveCurrent => {
+            if let Some(i) = *index {
+                model.remove(i);
+                *index = Some(i).filter(|&i| i < model.len());
+            }
+        }
+        CursorOp::SplitAfter => match *index {
+            None => model.clear(),
+            Some(i) => model.truncate(i + 1),
+        },
+        CursorOp::SplitBefore => match index {
+            None => model.clear(),
+            Some(i) => {
+                model.drain(..*i);
+                *i = 0;
+            }
+        },
+        CursorOp::SpliceAfter(ref values) => {
+            let at = index.map_or(0, |i| i + 1);
+            for (offset, value) in elems(values).into_iter().enumerate() {
+                model.insert(at + offset, value);
+            }
+        }
+        CursorOp::SpliceBefore(ref values) => {
+            let at = index.unwrap_or(len);
+            for (offset, value) in elems(values).into_iter().enumerate() {
+                model.insert(at + offset, value);
+            }
+            if let Some(i) = index {
+                *i += values.len();
+            }
+        }
+    }
+}
+
+pub fn run_doubly_linked_list(ops: &[DoublyOp]) {
+    let mut list: DoublyLinkedList<String> = DoublyLinkedList::new();
+    let mut model: VecDeque<String> = VecDeque::new();
+
+    for op in ops {
+        match op {
+            DoublyOp::List(op) => apply_list_op!(list, model, op),
+            DoublyOp::Cursor { from_back, ops } => {
+                let mut cursor = if *from_back {
+                    list.cursor_back_mut()
+                } else {
+                    list.cursor_front_mut()
+                };
+                let mut index = if model.is_empty() {
+                    None
+                } else if *from_back {
+                    Some(model.len() - 1)
+                } else {
+                    Some(0)
+                };
+
+                for op in ops {
+                    let split = match op {
+                        CursorOp::SplitAfter => Some(cursor.split_after()),
+                        CursorOp::SplitBefore => Some(cursor.split_before()),
+                        CursorOp::SpliceAfter(values) => {
+                            cursor.splice_after(elems(values).into_iter().collect());
+                            None
+                        }
+                        CursorOp::SpliceBefore(values) => {
+                            cursor.splice_before(elems(values).into_iter().collect());
+                            None
+                        }
+                        CursorOp::MoveNext => {
+                            cursor.move_next();
+                            None
+                        }
+                        CursorOp::MovePrev => {
+                            cursor.move_prev();
+                            None
+                        }
+                        CursorOp::InsertAfter(value) => {
+                            cursor.insert_after(elem(*value));
+                            None
+                        }
+                        CursorOp::InsertBefore(value) => {
+                            cursor.insert_before(elem(*value));
+                            None
+                        }
+                        CursorOp::RemoveCurrent => {
+                            let removed = cursor.remove_current();
+                            assert_eq!(removed.as_ref(), index.map(|i| &model[i]));
+                            None
+                        }
+                    };
+
+                    let before = model.clone();
+                    apply_cursor_op(&mut model, &mut index, op);
+                    if let Some(split) = split {
+                        // Whatever the model lost is exactly what was split off.
+                        let kept = model.len();
+                        let expected: Vec<&String> = match op {
+                            CursorOp::SplitAfter => before.range(kept..).collect(),
+                            _ => before.range(..before.len() - kept).collect(),
+                        };
+                        assert!(split.iter().eq(expected), "after {:?}", op);
+                    }
+
+                    assert_eq!(cursor.index(), index, "after {:?}", op);
+                    assert_eq!(cursor.current().cloned(), index.map(|i| model[i].clone()));
+                }
+            }
+        }
+
+        assert_eq!(list.len(), model.len(), "after {:?}", op);
+        assert!(list.iter().eq(model.iter()), "after {:?}", op);
+        assert!(list.iter().rev().eq(model.iter().rev()), "after {:?}", op);
+    }
+}
+
+#[derive(Arbitrary, Debug, Clone)]
+pub enum DequeOp {
+    PushFront(u8),
+    PushBack(u8),
+    PopFront,
+    PopBack,
+    Insert(u8, u8),
+    Remove(u8),
+    Set(u8, u8),
+    Truncate(u8),
+    Reserve(u8),
+    RotateLeft(u8),
+    RotateRight(u8),
+    MakeContiguous,
+    Extend(Vec<u8>),
+}
+
+pub fn run_vec_deque(ops: &[DequeOp]) {
+    let mut deque: DustVecDeque<String> = DustVecDeque::new();
+    let mut model: VecDeque<String> = VecDeque::new();
+
+    for op in ops {
+        match *op {
+            DequeOp::PushFront(value) => {
+                deque.push_front(elem(value));
+                model.push_front(elem(value));
+            }
+            DequeOp::PushBack(value) => {
+                deque.push_back(elem(value));
+                model.push_back(elem(value));
+            }
+            DequeOp::PopFront => assert_eq!(deque.pop_front(), model.pop_front()),
+            DequeOp::PopBack => assert_eq!(deque.pop_back(), model.pop_back()),
+            DequeOp::Insert(index, value) => {
+                let index = gap(index, model.len());
+                deque.insert(index, elem(value));
+                model.insert(index, elem(value));
+            }
+            DequeOp::Remove(index) if !model.is_empty() => {
+                let index = slot(index, model.len());
+                assert_eq!(Some(deque.remove(index)), model.remove(index));
+            }
+            DequeOp::Set(index, value) if !model.is_empty() => {
+                let index = slot(index, model.len());
+                deque[index] = elem(value);
+                model[index] = elem(value);
+            }
+            DequeOp::Remove(_) | DequeOp::Set(..) => {}
+            DequeOp::Truncate(len) => {
+                deque.truncate(len as usize);
+                model.truncate(len as usize);
+            }
+            DequeOp::Reserve(additional) => {
+                deque.reserve(additional as usize);
+                assert!(deque.capacity() >= deque.len() + additional as usize);
+            }
+            DequeOp::RotateLeft(n) => {
+                let n = gap(n, model.len());
+                deque.rotate_left(n);
+                model.rotate_left(n);
+            }
+            DequeOp::RotateRight(n) => {
+                let n = gap(n, model.len());
+                deque.rotate_right(n);
+                model.rotate_right(n);
+            }
+            DequeOp::MakeContiguous => {
+                assert_eq!(deque.make_contiguous(), model.make_contiguous());
+            }
+            DequeOp::Extend(ref values) => {
+                deque.extend(elems(values));
+                model.extend(elems(values));
+            }
+        }
+
+        assert_eq!(deque.len(), model.len(), "after {:?}", op);
+        assert!(deque.iter().eq(model.iter()), "after {:?}", op);
+        assert!(deque.iter().rev().eq(model.iter().rev()), "after {:?}", op);
+        let (front, back) = deque.as_slices();
+        assert!(front.iter().chain(back).eq(model.iter()), "after {:?}", op);
+        assert!(deque.capacity() >= deque.len());
+    }
+}